@@ -16,4 +16,110 @@ pub enum ZyuraError {
     Unauthorized,
     #[msg("Insufficient premium amount")]
     InsufficientPremium,
+    #[msg("Token mint does not match the configured USDC mint")]
+    InvalidUsdcMint,
+    #[msg("Risk pool vault does not match the configured vault")]
+    InvalidRiskPoolVault,
+    #[msg("Payout token account is not owned by the policyholder")]
+    InvalidPolicyholderAccount,
+    #[msg("LP share mint does not match the configured LP mint")]
+    InvalidLpMint,
+    #[msg("Insufficient LP shares")]
+    InsufficientLpShares,
+    #[msg("Pool has no assets backing outstanding LP shares")]
+    PoolInsolvent,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Token account is not owned by the signer")]
+    InvalidTokenAccountOwner,
+    #[msg("Pool reserves are insufficient to back this coverage")]
+    InsufficientPoolReserves,
+    #[msg("Withdrawal would leave the pool below required reserves")]
+    WithdrawalBreachesReserves,
+    #[msg("Invalid reserve ratio")]
+    InvalidReserveRatio,
+    #[msg("Policy does not belong to this product")]
+    PolicyProductMismatch,
+    #[msg("Invalid oracle feed")]
+    InvalidOracleFeed,
+    #[msg("Oracle feed is stale")]
+    StaleOracleFeed,
+    #[msg("Invalid oracle staleness window")]
+    InvalidOracleStaleness,
+    #[msg("Claim window has closed for this policy")]
+    ClaimWindowClosed,
+    #[msg("Claim window is still open for this policy")]
+    ClaimWindowOpen,
+    #[msg("Missing or invalid oracle outcome attestation")]
+    InvalidOracleAttestation,
+    #[msg("Oracle outcome attestation is stale")]
+    StaleOracleAttestation,
+    #[msg("No oracle authority is configured")]
+    OracleAuthorityNotSet,
+    #[msg("Cancellation cutoff for this policy has passed")]
+    CancellationCutoffPassed,
+    #[msg("Invalid cancellation fee")]
+    InvalidCancellationFee,
+    #[msg("Delay bands must ascend in delay and payout, with payouts between 1 and 10000 bps")]
+    InvalidDelayBands,
+    #[msg("Flight outcome is not covered by this policy")]
+    OutcomeNotCovered,
+    #[msg("Invalid outcome payout")]
+    InvalidOutcomePayout,
+    #[msg("Role member or admin must be a non-default public key")]
+    InvalidRoleMember,
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    #[msg("A change is already pending; execute or cancel it first")]
+    ChangeAlreadyPending,
+    #[msg("No change is pending")]
+    NoPendingChange,
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    #[msg("Product still has active policies")]
+    ProductHasActivePolicies,
+    #[msg("A product already exists with this id")]
+    ProductAlreadyExists,
+    #[msg("Premium exceeds the buyer's max_premium")]
+    PremiumAboveMax,
+    #[msg("Departure is too soon; sales for this flight have closed")]
+    DepartureTooSoon,
+    #[msg("Departure is beyond the product's booking horizon")]
+    DepartureTooFar,
+    #[msg("Booking horizon must be positive and longer than the minimum lead time")]
+    InvalidSalesWindow,
+    #[msg("Flight number must be a 2-character IATA or 3-letter ICAO carrier code, 1-4 digits and an optional letter")]
+    InvalidFlightNumber,
+    #[msg("Departure time does not match the flight's scheduled departure")]
+    FlightScheduleMismatch,
+    #[msg("Policy does not belong to this flight")]
+    PolicyFlightMismatch,
+    #[msg("Outcome differs from the outcome already recorded for this flight")]
+    FlightOutcomeMismatch,
+    #[msg("Flight already has a reported outcome")]
+    FlightAlreadySettled,
+    #[msg("Settlement accounts must be writable (policy, policyholder token account) pairs")]
+    InvalidSettlementAccounts,
+    #[msg("Purchase would exceed the coverage cap for this flight")]
+    FlightCoverageCapExceeded,
+    #[msg("Purchase would exceed the product's coverage cap")]
+    ProductCoverageCapExceeded,
+    #[msg("Purchase would exceed the per-policyholder coverage cap for this flight")]
+    PolicyholderCoverageCapExceeded,
+    #[msg("Coverage range must be non-empty, start above zero and be a whole number of steps wide")]
+    InvalidCoverageRange,
+    #[msg("Coverage must be within the product's range and a whole number of steps above its minimum")]
+    InvalidCoverageAmount,
+    #[msg("Invalid pricing curve")]
+    InvalidPricingCurve,
+    #[msg("Oracle feed is not the one registered for this flight")]
+    OracleFeedMismatch,
+    #[msg("Only a cancellation can be reported before the flight's scheduled departure")]
+    FlightNotDeparted,
+    #[msg("Released coverage exceeds the recorded liability")]
+    LiabilityUnderflow,
+    #[msg("Product id is free; only an id held by an existing product can be skipped")]
+    ProductIdNotTaken,
 }
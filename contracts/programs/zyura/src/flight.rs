@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::errors::ZyuraError;
+
+/// Length of a canonical flight code: 3-char carrier, up to 4 digits and an optional suffix letter
+pub const FLIGHT_CODE_LEN: usize = 8;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Normalize a flight number such as "ai 0101", "AI101" or "AIC101" to its canonical code,
+/// zero-padded to `FLIGHT_CODE_LEN` bytes.
+///
+/// Spaces are ignored and letters uppercased. The carrier is either a 3-letter ICAO code or a
+/// 2-character IATA code (letters or digits, not both digits), followed by a 1-4 digit flight
+/// number whose leading zeros are dropped and an optional operational suffix letter.
+pub fn normalize_flight_number(raw: &str) -> Result<[u8; FLIGHT_CODE_LEN]> {
+    let chars: Vec<u8> = raw
+        .bytes()
+        .filter(|c| *c != b' ')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    require!(
+        chars.len() >= 3 && chars.iter().all(u8::is_ascii_alphanumeric),
+        ZyuraError::InvalidFlightNumber
+    );
+
+    let icao = chars.len() >= 4 && chars[..3].iter().all(u8::is_ascii_alphabetic) && chars[3].is_ascii_digit();
+    let carrier_len = if icao { 3 } else { 2 };
+    let carrier = &chars[..carrier_len];
+    require!(
+        !carrier.iter().all(u8::is_ascii_digit) && chars[carrier_len].is_ascii_digit(),
+        ZyuraError::InvalidFlightNumber
+    );
+
+    let rest = &chars[carrier_len..];
+    let digits_len = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+    let suffix = &rest[digits_len..];
+    require!(
+        (1..=4).contains(&digits_len) && suffix.len() <= 1 && suffix.iter().all(u8::is_ascii_alphabetic),
+        ZyuraError::InvalidFlightNumber
+    );
+    let number = rest[..digits_len]
+        .iter()
+        .fold(0u16, |n, d| n * 10 + (d - b'0') as u16);
+    require!(number > 0, ZyuraError::InvalidFlightNumber);
+
+    let mut code = [0u8; FLIGHT_CODE_LEN];
+    let mut len = carrier_len;
+    code[..len].copy_from_slice(carrier);
+    for digit in number.to_string().bytes() {
+        code[len] = digit;
+        len += 1;
+    }
+    if let Some(letter) = suffix.first() {
+        code[len] = *letter;
+    }
+    Ok(code)
+}
+
+/// Seed form of `raw`'s canonical code; all zeros when it does not parse, which `register_flight`
+/// then rejects with `InvalidFlightNumber`, so no flight is ever registered at that seed.
+pub fn flight_code_seed(raw: &str) -> [u8; FLIGHT_CODE_LEN] {
+    normalize_flight_number(raw).unwrap_or_default()
+}
+
+/// Canonical code as a string, without its zero padding.
+pub fn flight_code_str(code: &[u8; FLIGHT_CODE_LEN]) -> String {
+    code.iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as char)
+        .collect()
+}
+
+/// UTC calendar day (days since the unix epoch) of a departure timestamp.
+pub fn departure_date(departure_time: i64) -> i64 {
+    departure_time.div_euclid(SECONDS_PER_DAY)
+}
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::state::Config;
+use crate::state::{Config, ConfigChange, Role};
+use crate::instructions::timelock;
 use crate::errors::ZyuraError;
 
 #[derive(Accounts)]
@@ -16,7 +17,7 @@ pub struct SetPauseStatus<'info> {
 }
 
 pub fn set_pause_status(ctx: Context<SetPauseStatus>, paused: bool) -> Result<()> {
-    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    ctx.accounts.config.require_role(Role::Pauser, &ctx.accounts.admin.key())?;
     
     ctx.accounts.config.paused = paused;
     msg!("Protocol pause status set to: {}", paused);
@@ -24,12 +25,79 @@ pub fn set_pause_status(ctx: Context<SetPauseStatus>, paused: bool) -> Result<()
 }
 
 #[derive(Accounts)]
-pub struct CloseConfig<'info> {
+pub struct SetReserveRatio<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn set_reserve_ratio(ctx: Context<SetReserveRatio>, reserve_ratio_bps: u16) -> Result<()> {
+    ctx.accounts.config.require_role(Role::Treasury, &ctx.accounts.admin.key())?;
+    require!(reserve_ratio_bps > 0, ZyuraError::InvalidReserveRatio);
+    
+    timelock::queue_config_change(&mut ctx.accounts.config, ConfigChange::ReserveRatio { reserve_ratio_bps })
+}
+
+#[derive(Accounts)]
+pub struct SetExposureCaps<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn set_exposure_caps(
+    ctx: Context<SetExposureCaps>,
+    max_flight_coverage: u64,
+    max_policyholder_flight_coverage: u64,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::Treasury, &ctx.accounts.admin.key())?;
+    
+    timelock::queue_config_change(
+        &mut ctx.accounts.config,
+        ConfigChange::ExposureCaps {
+            max_flight_coverage,
+            max_policyholder_flight_coverage,
+        },
+    )
+}
+
+#[derive(Accounts)]
+pub struct SetOracleMaxStaleness<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn set_oracle_max_staleness(ctx: Context<SetOracleMaxStaleness>, max_staleness_secs: i64) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(max_staleness_secs > 0, ZyuraError::InvalidOracleStaleness);
+    
+    timelock::queue_config_change(&mut ctx.accounts.config, ConfigChange::OracleMaxStaleness { max_staleness_secs })
+}
+
+#[derive(Accounts)]
+pub struct SetOracleAuthority<'info> {
     #[account(
         mut,
         seeds = [b"config"],
-        bump = config.bump,
-        close = admin
+        bump = config.bump
     )]
     pub config: Account<'info, Config>,
     
@@ -37,9 +105,178 @@ pub struct CloseConfig<'info> {
     pub admin: Signer<'info>,
 }
 
-pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
+pub fn set_oracle_authority(ctx: Context<SetOracleAuthority>, oracle_authority: Pubkey) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    
+    timelock::queue_config_change(&mut ctx.accounts.config, ConfigChange::OracleAuthority { oracle_authority })
+}
+
+#[derive(Accounts)]
+pub struct SetTimelockDelay<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, timelock_delay_secs: i64) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(
+        (Config::MIN_TIMELOCK_DELAY_SECS..=Config::MAX_TIMELOCK_DELAY_SECS).contains(&timelock_delay_secs),
+        ZyuraError::InvalidTimelockDelay
+    );
+    
+    // The delay itself is timelocked, so it cannot be shortened to rush another change through
+    timelock::queue_config_change(&mut ctx.accounts.config, ConfigChange::TimelockDelay { timelock_delay_secs })
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn grant_role(ctx: Context<UpdateRole>, role: Role, member: Pubkey) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(member != Pubkey::default(), ZyuraError::InvalidRoleMember);
+    
+    set_role_holder(&mut ctx.accounts.config, role, member);
+    emit!(RoleGranted { role, member });
+    msg!("Role {:?} granted to {}", role, member);
+    Ok(())
+}
+
+pub fn revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    
+    let member = ctx.accounts.config.role_holder(role);
+    set_role_holder(&mut ctx.accounts.config, role, Pubkey::default());
+    emit!(RoleRevoked { role, member });
+    msg!("Role {:?} revoked from {}", role, member);
+    Ok(())
+}
+
+fn set_role_holder(config: &mut Config, role: Role, member: Pubkey) {
+    match role {
+        Role::Pauser => config.pauser = member,
+        Role::ProductManager => config.product_manager = member,
+        Role::Settler => config.settler = member,
+        Role::Treasury => config.treasury = member,
+    }
+}
+
+#[event]
+pub struct RoleGranted {
+    pub role: Role,
+    pub member: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub member: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(new_admin != Pubkey::default(), ZyuraError::InvalidRoleMember);
+    
+    ctx.accounts.config.pending_admin = new_admin;
+    emit!(AdminTransferProposed {
+        current_admin: ctx.accounts.config.admin,
+        pending_admin: new_admin,
+    });
+    msg!("Admin transfer proposed to {}", new_admin);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// Nominated admin; signing proves control of the new key
+    pub new_admin: Signer<'info>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(config.pending_admin != Pubkey::default(), ZyuraError::NoPendingAdmin);
+    require!(config.pending_admin == ctx.accounts.new_admin.key(), ZyuraError::Unauthorized);
+    
+    let previous_admin = config.admin;
+    config.admin = config.pending_admin;
+    config.pending_admin = Pubkey::default();
+    emit!(AdminTransferred {
+        previous_admin,
+        new_admin: config.admin,
+    });
+    msg!("Admin transferred from {} to {}", previous_admin, config.admin);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
     require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(ctx.accounts.config.pending_admin != Pubkey::default(), ZyuraError::NoPendingAdmin);
     
-    msg!("Config account closed by admin");
+    let pending_admin = ctx.accounts.config.pending_admin;
+    ctx.accounts.config.pending_admin = Pubkey::default();
+    emit!(AdminTransferCancelled { pending_admin });
+    msg!("Admin transfer to {} cancelled", pending_admin);
     Ok(())
 }
+
+#[event]
+pub struct AdminTransferProposed {
+    pub current_admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub pending_admin: Pubkey,
+}
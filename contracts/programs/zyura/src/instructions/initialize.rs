@@ -1,21 +1,63 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use crate::state::Config;
 use crate::errors::ZyuraError;
+use crate::program::Zyura;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
+    // Plain `init`: once created, the config (admin, oracle program, pause flag) cannot be re-initialized
     #[account(
-        init_if_needed,
+        init,
         payer = payer,
         space = 8 + Config::INIT_SPACE,
         seeds = [b"config"],
         bump
     )]
     pub config: Account<'info, Config>,
+
+    pub usdc_mint: Account<'info, Mint>,
+
+    // Risk pool vault is a program-owned token account; the config PDA is its authority
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"risk_pool_vault"],
+        bump,
+        token::mint = usdc_mint,
+        token::authority = config,
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+
+    // LP share mint; shares are minted against pool NAV and redeemed pro-rata
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"lp_mint"],
+        bump,
+        mint::decimals = usdc_mint.decimals,
+        mint::authority = config,
+    )]
+    pub lp_mint: Account<'info, Mint>,
     
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    // Only the program's upgrade authority can create the config, so nobody can front-run the deployer and take
+    // the admin role (and with it the risk pool vault and LP mint)
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ZyuraError::Unauthorized
+    )]
+    pub upgrade_authority: Signer<'info>,
+    
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ZyuraError::Unauthorized
+    )]
+    pub program: Program<'info, Zyura>,
+    
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -25,15 +67,18 @@ pub fn initialize(
     usdc_mint: Pubkey,
     switchboard_program: Pubkey,
 ) -> Result<()> {
-    let config = &mut ctx.accounts.config;
-
-    if config.admin != Pubkey::default() {
-        require!(config.admin == admin, ZyuraError::Unauthorized);
-    }
+    require_keys_eq!(ctx.accounts.usdc_mint.key(), usdc_mint, ZyuraError::InvalidUsdcMint);
 
+    let config = &mut ctx.accounts.config;
     config.admin = admin;
     config.usdc_mint = usdc_mint;
     config.switchboard_program = switchboard_program;
+    config.risk_pool_vault = ctx.accounts.risk_pool_vault.key();
+    config.lp_mint = ctx.accounts.lp_mint.key();
+    config.reserve_ratio_bps = Config::DEFAULT_RESERVE_RATIO_BPS;
+    config.oracle_max_staleness_secs = Config::DEFAULT_ORACLE_MAX_STALENESS_SECS;
+    config.timelock_delay_secs = Config::DEFAULT_TIMELOCK_DELAY_SECS;
+    config.next_product_id = 1;
     config.paused = false;
     config.bump = ctx.bumps.config;
     
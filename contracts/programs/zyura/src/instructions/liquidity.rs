@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
 use crate::state::{Config, LiquidityProvider};
 use crate::errors::ZyuraError;
 
@@ -22,16 +23,35 @@ pub struct DepositLiquidity<'info> {
     pub liquidity_provider: Account<'info, LiquidityProvider>,
     
     #[account(
-        mut
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
     )]
     pub risk_pool_vault: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = config.lp_mint @ ZyuraError::InvalidLpMint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint
+    )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user,
+    )]
+    pub user_lp_token_account: Account<'info, TokenAccount>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
@@ -45,30 +65,77 @@ pub struct WithdrawLiquidity<'info> {
     )]
     pub config: Account<'info, Config>,
     
+    /// Deposit/withdrawal tally only; LP shares received by transfer are redeemable without one
     #[account(
-        mut,
+        init_if_needed,
+        payer = user,
+        space = 8 + LiquidityProvider::INIT_SPACE,
         seeds = [b"liquidity_provider", user.key().as_ref()],
-        bump = liquidity_provider.bump
+        bump
     )]
     pub liquidity_provider: Account<'info, LiquidityProvider>,
     
     #[account(
-        mut
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
     )]
     pub risk_pool_vault: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = config.lp_mint @ ZyuraError::InvalidLpMint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = user_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint,
+        constraint = user_usdc_account.owner == user.key() @ ZyuraError::InvalidTokenAccountOwner
+    )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
-    /// CHECK: Only used to derive the liquidity_provider PDA. Not required to sign for vault outflow.
-    #[account(mut)]
-    pub user: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = user_lp_token_account.mint == lp_mint.key() @ ZyuraError::InvalidLpMint,
+        constraint = user_lp_token_account.owner == user.key() @ ZyuraError::InvalidTokenAccountOwner
+    )]
+    pub user_lp_token_account: Account<'info, TokenAccount>,
     
-    /// Admin authorizes transfers from the vault
+    /// LP exiting the pool; no admin co-signature, the vault transfer is signed by the config PDA
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub user: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Virtual shares and USDC added to both sides of the share price. An empty pool still mints 1:1, but USDC sitting
+/// in the vault without shares (premiums, donations) is not handed to the first depositor, and inflating the share
+/// price with a donation costs the attacker about as much as it takes from the next depositor.
+const VIRTUAL_SHARES: u128 = 1_000_000;
+const VIRTUAL_ASSETS: u128 = 1_000_000;
+
+/// Shares minted for a deposit of `amount` USDC into a pool holding `pool_value` USDC
+/// with `share_supply` LP shares outstanding.
+fn shares_for_deposit(amount: u64, share_supply: u64, pool_value: u64) -> Result<u64> {
+    let shares = (amount as u128)
+        .checked_mul(share_supply as u128 + VIRTUAL_SHARES)
+        .ok_or(ZyuraError::MathOverflow)?
+        / (pool_value as u128 + VIRTUAL_ASSETS);
+    u64::try_from(shares).map_err(|_| error!(ZyuraError::MathOverflow))
+}
+
+/// Pro-rata USDC owed for redeeming `shares` out of `share_supply` against `pool_value` USDC.
+fn usdc_for_shares(shares: u64, share_supply: u64, pool_value: u64) -> Result<u64> {
+    require!(share_supply > 0, ZyuraError::InsufficientLpShares);
+    let amount = (shares as u128)
+        .checked_mul(pool_value as u128 + VIRTUAL_ASSETS)
+        .ok_or(ZyuraError::MathOverflow)?
+        / (share_supply as u128 + VIRTUAL_SHARES);
+    // Once losses push the share price below 1:1 the virtual side prices shares slightly high;
+    // never owe more than the vault holds
+    let amount = amount.min(pool_value as u128);
+    u64::try_from(amount).map_err(|_| error!(ZyuraError::MathOverflow))
 }
 
 pub fn deposit_liquidity(
@@ -78,6 +145,14 @@ pub fn deposit_liquidity(
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
     require!(amount > 0, ZyuraError::InvalidAmount);
     
+    // Price shares against pool NAV before the deposit lands in the vault
+    let shares = shares_for_deposit(
+        amount,
+        ctx.accounts.lp_mint.supply,
+        ctx.accounts.risk_pool_vault.amount,
+    )?;
+    require!(shares > 0, ZyuraError::InvalidAmount);
+    
     // Transfer USDC from user to risk pool
     let transfer_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
@@ -89,6 +164,21 @@ pub fn deposit_liquidity(
     );
     token::transfer(transfer_ctx, amount)?;
     
+    // Mint LP shares to the depositor, signed by the config PDA that is the mint authority
+    let config_bump = ctx.accounts.config.bump;
+    let signer_seeds: &[&[u8]] = &[b"config", &[config_bump]];
+    let signer = &[signer_seeds];
+    let mint_to_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.user_lp_token_account.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
+        },
+        signer,
+    );
+    token::mint_to(mint_to_ctx, shares)?;
+    
     // Update liquidity provider account
     let lp = &mut ctx.accounts.liquidity_provider;
     if lp.provider == Pubkey::default() {
@@ -96,39 +186,72 @@ pub fn deposit_liquidity(
     } else {
         require!(lp.provider == ctx.accounts.user.key(), ZyuraError::Unauthorized);
     }
-    lp.total_deposited += amount;
-    lp.active_deposit += amount;
+    lp.total_deposited = lp.total_deposited.checked_add(amount).ok_or(ZyuraError::MathOverflow)?;
     lp.bump = ctx.bumps.liquidity_provider;
     
-    msg!("Liquidity deposited: {} USDC", amount);
+    msg!("Liquidity deposited: {} USDC for {} LP shares", amount, shares);
     Ok(())
 }
 
 pub fn withdraw_liquidity(
     ctx: Context<WithdrawLiquidity>,
-    amount: u64,
+    shares: u64,
 ) -> Result<()> {
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
+    require!(shares > 0, ZyuraError::InvalidAmount);
+    require!(ctx.accounts.user_lp_token_account.amount >= shares, ZyuraError::InsufficientLpShares);
+    
+    let amount = usdc_for_shares(
+        shares,
+        ctx.accounts.lp_mint.supply,
+        ctx.accounts.risk_pool_vault.amount,
+    )?;
     require!(amount > 0, ZyuraError::InvalidAmount);
-    require!(ctx.accounts.liquidity_provider.active_deposit >= amount, ZyuraError::InvalidAmount);
-    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+
+    // Funds backing Active policies cannot leave the vault
+    let remaining = ctx.accounts.risk_pool_vault.amount
+        .checked_sub(amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    let config = &ctx.accounts.config;
+    require!(
+        remaining >= config.required_reserve(config.total_active_coverage)?,
+        ZyuraError::WithdrawalBreachesReserves
+    );
     
-    // Transfer USDC from risk pool to user
-    let transfer_ctx = CpiContext::new(
+    // Burn the redeemed shares from the LP
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.user_lp_token_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::burn(burn_ctx, shares)?;
+    
+    // Transfer USDC from risk pool to user, signed by the config PDA that owns the vault
+    let config_bump = ctx.accounts.config.bump;
+    let signer_seeds: &[&[u8]] = &[b"config", &[config_bump]];
+    let signer = &[signer_seeds];
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.risk_pool_vault.to_account_info(),
             to: ctx.accounts.user_usdc_account.to_account_info(),
-            authority: ctx.accounts.admin.to_account_info(),
+            authority: ctx.accounts.config.to_account_info(),
         },
+        signer,
     );
     token::transfer(transfer_ctx, amount)?;
     
     // Update liquidity provider account
     let lp = &mut ctx.accounts.liquidity_provider;
-    lp.total_withdrawn += amount;
-    lp.active_deposit -= amount;
+    if lp.provider == Pubkey::default() {
+        lp.provider = ctx.accounts.user.key();
+        lp.bump = ctx.bumps.liquidity_provider;
+    }
+    lp.total_withdrawn = lp.total_withdrawn.checked_add(amount).ok_or(ZyuraError::MathOverflow)?;
     
-    msg!("Liquidity withdrawn: {} USDC", amount);
+    msg!("Liquidity withdrawn: {} LP shares for {} USDC", shares, amount);
     Ok(())
-}
\ No newline at end of file
+}
@@ -3,9 +3,11 @@ pub mod product;
 pub mod policy;
 pub mod liquidity;
 pub mod admin;
+pub mod timelock;
 
 pub use initialize::*;
 pub use product::*;
 pub use policy::*;
 pub use liquidity::*;
 pub use admin::*;
+pub use timelock::*;
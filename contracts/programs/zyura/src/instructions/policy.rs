@@ -1,16 +1,69 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer, FreezeAccount};
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount, Transfer, FreezeAccount, ThawAccount};
 use mpl_token_metadata::instructions as mpl_instructions;
 use mpl_token_metadata::accounts::{MasterEdition, Metadata};
 use mpl_token_metadata::types::DataV2;
 use mpl_token_metadata::ID as TOKEN_METADATA_PROGRAM_ID;
-use crate::state::{Config, Product, Policy, PolicyStatus};
+use crate::state::{Config, Flight, FlightExposure, FlightOutcome, Product, Policy, Policyholder, PolicyStatus, Role};
 use crate::errors::ZyuraError;
+use crate::{flight, oracle};
 
 #[derive(Accounts)]
-#[instruction(policy_id: u64)]
+pub struct QuotePremium<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(flight_number: String, departure_time: i64)]
+pub struct RegisterFlight<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    // One account per flight and date; a schedule cannot be registered twice.
+    // The computed seeds are sliced rather than `.as_ref()`'d so IDL generation leaves this address to the client
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Flight::INIT_SPACE,
+        seeds = [
+            b"flight",
+            &flight::flight_code_seed(&flight_number)[..],
+            &flight::departure_date(departure_time).to_le_bytes()[..]
+        ],
+        bump
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    /// Settler (or admin) vouching for the schedule buyers insure against
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(flight_number: String, departure_time: i64)]
 pub struct PurchasePolicy<'info> {
     #[account(
         mut,
@@ -26,21 +79,57 @@ pub struct PurchasePolicy<'info> {
     )]
     pub product: Account<'info, Product>,
     
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Policyholder::INIT_SPACE,
+        seeds = [b"policyholder", user.key().as_ref()],
+        bump
+    )]
+    pub policyholder: Account<'info, Policyholder>,
+    
+    // Derived from the buyer's policy counter, so concurrent buyers never contend for an id
     #[account(
         init,
         payer = user,
         space = 8 + Policy::INIT_SPACE,
-        seeds = [b"policy", policy_id.to_le_bytes().as_ref()],
+        seeds = [b"policy", user.key().as_ref(), policyholder.policy_count.to_le_bytes().as_ref()],
         bump
     )]
     pub policy: Account<'info, Policy>,
     
+    // Registered beforehand by the settler; unregistered flights cannot be insured
+    #[account(
+        mut,
+        seeds = [
+            b"flight",
+            &flight::flight_code_seed(&flight_number)[..],
+            &flight::departure_date(departure_time).to_le_bytes()[..]
+        ],
+        bump = flight.bump
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    // Buyer's coverage on this flight, checked against the per-policyholder cap
     #[account(
-        mut
+        init_if_needed,
+        payer = user,
+        space = 8 + FlightExposure::INIT_SPACE,
+        seeds = [b"flight_exposure", flight.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub flight_exposure: Account<'info, FlightExposure>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
     )]
     pub risk_pool_vault: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint
+    )]
     pub user_usdc_account: Account<'info, TokenAccount>,
     
     #[account(mut)]
@@ -107,40 +196,385 @@ pub struct ProcessPayout<'info> {
     
     #[account(
         mut,
-        seeds = [b"policy", policy.id.to_le_bytes().as_ref()],
-        bump = policy.bump
+        seeds = [b"policy", policy.policyholder.as_ref(), policy.id.to_le_bytes().as_ref()],
+        bump = policy.bump,
+        constraint = policy.product_id == product.id @ ZyuraError::PolicyProductMismatch
     )]
     pub policy: Account<'info, Policy>,
     
+    #[account(
+        mut,
+        address = policy.flight @ ZyuraError::PolicyFlightMismatch
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = policyholder_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint,
+        constraint = policyholder_usdc_account.owner == policy.policyholder @ ZyuraError::InvalidPolicyholderAccount
+    )]
+    pub policyholder_usdc_account: Account<'info, TokenAccount>,
+    
+    /// Settler (or admin) approves the payout; the vault transfer itself is signed by the config PDA
     #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWithOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(
+        mut,
+        seeds = [b"policy", policy.policyholder.as_ref(), policy.id.to_le_bytes().as_ref()],
+        bump = policy.bump,
+        constraint = policy.product_id == product.id @ ZyuraError::PolicyProductMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+    
+    #[account(
+        mut,
+        address = policy.flight @ ZyuraError::PolicyFlightMismatch
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
     pub risk_pool_vault: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        constraint = policyholder_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint,
+        constraint = policyholder_usdc_account.owner == policy.policyholder @ ZyuraError::InvalidPolicyholderAccount
+    )]
+    pub policyholder_usdc_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Switchboard aggregator feed reporting the flight delay in minutes; must be the feed
+    /// registered for the flight, owned by the configured Switchboard program, and is decoded in the handler
+    #[account(
+        address = flight.oracle_feed @ ZyuraError::OracleFeedMismatch,
+        owner = config.switchboard_program @ ZyuraError::InvalidOracleFeed
+    )]
+    pub oracle_feed: UncheckedAccount<'info>,
+    
+    /// Settler (or admin) submits the claim; the delay is taken from the oracle feed
     #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPayout<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(
+        mut,
+        seeds = [b"policy", policy.policyholder.as_ref(), policy.id.to_le_bytes().as_ref()],
+        bump = policy.bump,
+        constraint = policy.product_id == product.id @ ZyuraError::PolicyProductMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+    
+    #[account(
+        mut,
+        address = policy.flight @ ZyuraError::PolicyFlightMismatch
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = policyholder_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint,
+        constraint = policyholder_usdc_account.owner == policy.policyholder @ ZyuraError::InvalidPolicyholderAccount
+    )]
     pub policyholder_usdc_account: Account<'info, TokenAccount>,
     
-    /// Admin authorizes transfers from the vault
+    /// CHECK: Instructions sysvar, used to inspect the preceding Ed25519 verification instruction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFlight<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    // Each settled policy must reference this flight
     #[account(mut)]
+    pub flight: Account<'info, Flight>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+    
+    /// Settler (or admin) reports the outcome; payouts are signed by the config PDA
     pub admin: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct ExpirePolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(
+        mut,
+        seeds = [b"policy", policy.policyholder.as_ref(), policy.id.to_le_bytes().as_ref()],
+        bump = policy.bump,
+        constraint = policy.product_id == product.id @ ZyuraError::PolicyProductMismatch
+    )]
+    pub policy: Account<'info, Policy>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(
+        mut,
+        seeds = [b"policy", policy.policyholder.as_ref(), policy.id.to_le_bytes().as_ref()],
+        bump = policy.bump,
+        constraint = policy.product_id == product.id @ ZyuraError::PolicyProductMismatch,
+        constraint = policy.policyholder == user.key() @ ZyuraError::Unauthorized
+    )]
+    pub policy: Account<'info, Policy>,
+    
+    #[account(
+        mut,
+        address = policy.flight @ ZyuraError::PolicyFlightMismatch
+    )]
+    pub flight: Account<'info, Flight>,
+    
+    #[account(
+        mut,
+        seeds = [b"flight_exposure", policy.flight.as_ref(), user.key().as_ref()],
+        bump = flight_exposure.bump
+    )]
+    pub flight_exposure: Account<'info, FlightExposure>,
+    
+    #[account(
+        mut,
+        address = config.risk_pool_vault @ ZyuraError::InvalidRiskPoolVault
+    )]
+    pub risk_pool_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = user_usdc_account.mint == config.usdc_mint @ ZyuraError::InvalidUsdcMint,
+        constraint = user_usdc_account.owner == user.key() @ ZyuraError::InvalidTokenAccountOwner
+    )]
+    pub user_usdc_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = policy.nft_mint
+    )]
+    pub policy_nft_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = policy_nft_mint,
+        associated_token::authority = user,
+    )]
+    pub user_policy_nft_ata: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that is mint and freeze authority of every policy NFT
+    #[account(
+        seeds = [b"policy_mint_authority"],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn quote_premium(ctx: Context<QuotePremium>, coverage_amount: u64) -> Result<u64> {
+    quote(
+        &ctx.accounts.config,
+        &ctx.accounts.product,
+        ctx.accounts.risk_pool_vault.amount,
+        coverage_amount,
+    )
+}
+
+/// Premium for `coverage_amount` of `product`, priced at the pool utilization the new policy would bring.
+fn quote(config: &Config, product: &Product, pool_value: u64, coverage_amount: u64) -> Result<u64> {
+    product.check_coverage(coverage_amount)?;
+    let utilization_bps = config.utilization_bps(pool_value, coverage_amount)?;
+    product.premium(coverage_amount, utilization_bps)
+}
+
+pub fn register_flight(
+    ctx: Context<RegisterFlight>,
+    flight_number: String,
+    departure_time: i64,
+    oracle_feed: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::Settler, &ctx.accounts.admin.key())?;
+    let flight_code = flight::normalize_flight_number(&flight_number)?;
+    
+    let flight = &mut ctx.accounts.flight;
+    flight.code = flight_code;
+    flight.departure_date = flight::departure_date(departure_time);
+    flight.scheduled_departure = departure_time;
+    flight.oracle_feed = oracle_feed;
+    flight.outcome = None;
+    flight.outcome_reported_at = 0;
+    flight.outcome_final = false;
+    flight.total_coverage = 0;
+    flight.bump = ctx.bumps.flight;
+    
+    msg!("Flight {} registered departing at {}", flight::flight_code_str(&flight_code), departure_time);
+    Ok(())
+}
+
 pub fn purchase_policy(
     ctx: Context<PurchasePolicy>,
-    policy_id: u64,
-    flight_number: String,
+    // Only locates the Flight account; the policy records the registered canonical code
+    _flight_number: String,
     departure_time: i64,
-    premium_amount: u64,
+    coverage_amount: u64,
+    max_premium: u64,
     create_metadata: bool,
     metadata_uri: String,
-) -> Result<()> {
+) -> Result<u64> {
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
     require!(ctx.accounts.product.active, ZyuraError::ProductInactive);
-    // Enforce minimum premium based on product's premium_rate_bps
-    let required_premium: u64 = ((ctx.accounts.product.coverage_amount as u128
-        * ctx.accounts.product.premium_rate_bps as u128)
-        / 10_000u128) as u64;
-    require!(premium_amount >= required_premium, ZyuraError::InsufficientPremium);
+    // Charge exactly the quoted premium; `max_premium` guards against terms or pool utilization moving under the buyer
+    let premium_amount = quote(
+        &ctx.accounts.config,
+        &ctx.accounts.product,
+        ctx.accounts.risk_pool_vault.amount,
+        coverage_amount,
+    )?;
+    require!(premium_amount <= max_premium, ZyuraError::PremiumAboveMax);
+    
+    // Only sell ahead of departure, so buyers cannot insure a flight already known to be late
+    let now = Clock::get()?.unix_timestamp;
+    check_sales_window(&ctx.accounts.product, departure_time, now)?;
+    
+    // The buyer must insure the schedule the settler registered
+    let flight = &ctx.accounts.flight;
+    require!(flight.scheduled_departure == departure_time, ZyuraError::FlightScheduleMismatch);
+    require!(flight.outcome.is_none(), ZyuraError::FlightAlreadySettled);
+    let flight_number = flight::flight_code_str(&flight.code);
+
+    // Concentration limits on the flight, the buyer's position on it and the product
+    let flight_coverage = ctx.accounts.flight.total_coverage
+        .checked_add(coverage_amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    require!(
+        within_cap(flight_coverage, ctx.accounts.config.max_flight_coverage),
+        ZyuraError::FlightCoverageCapExceeded
+    );
+    let holder_coverage = ctx.accounts.flight_exposure.coverage
+        .checked_add(coverage_amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    require!(
+        within_cap(holder_coverage, ctx.accounts.config.max_policyholder_flight_coverage),
+        ZyuraError::PolicyholderCoverageCapExceeded
+    );
+    let product_coverage = ctx.accounts.product.active_coverage
+        .checked_add(coverage_amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    require!(
+        within_cap(product_coverage, ctx.accounts.product.max_active_coverage),
+        ZyuraError::ProductCoverageCapExceeded
+    );
+    
+    // Refuse to sell coverage the vault (including this premium) cannot back at the reserve ratio
+    let new_liability = ctx.accounts.config.total_active_coverage
+        .checked_add(coverage_amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    let pool_after_premium = ctx.accounts.risk_pool_vault.amount
+        .checked_add(premium_amount)
+        .ok_or(ZyuraError::MathOverflow)?;
+    require!(
+        pool_after_premium >= ctx.accounts.config.required_reserve(new_liability)?,
+        ZyuraError::InsufficientPoolReserves
+    );
     
     // Transfer USDC from user to risk pool
     let transfer_ctx = CpiContext::new(
@@ -153,18 +587,48 @@ pub fn purchase_policy(
     );
     token::transfer(transfer_ctx, premium_amount)?;
     
+    // Allocate the next id from the buyer's counter
+    let policyholder = &mut ctx.accounts.policyholder;
+    let policy_id = policyholder.policy_count;
+    policyholder.policyholder = ctx.accounts.user.key();
+    policyholder.policy_count = policy_id.checked_add(1).ok_or(ZyuraError::MathOverflow)?;
+    policyholder.bump = ctx.bumps.policyholder;
+    
     // Create policy account
     let policy = &mut ctx.accounts.policy;
     policy.id = policy_id;
     policy.policyholder = ctx.accounts.user.key();
     policy.product_id = ctx.accounts.product.id;
     policy.flight_number = flight_number.clone();
+    policy.flight = ctx.accounts.flight.key();
     policy.departure_time = departure_time;
     policy.premium_paid = premium_amount;
-    policy.coverage_amount = ctx.accounts.product.coverage_amount;
+    policy.coverage_amount = coverage_amount;
+    policy.delay_threshold_minutes = ctx.accounts.product.delay_threshold_minutes;
+    policy.claim_window_hours = ctx.accounts.product.claim_window_hours;
+    policy.premium_rate_bps = ctx.accounts.product.premium_rate_bps;
+    policy.delay_bands = ctx.accounts.product.delay_bands.clone();
+    policy.flight_cancelled_payout_bps = ctx.accounts.product.flight_cancelled_payout_bps;
+    policy.flight_diverted_payout_bps = ctx.accounts.product.flight_diverted_payout_bps;
+    policy.cancellation_cutoff_hours = ctx.accounts.product.cancellation_cutoff_hours;
+    policy.cancellation_fee_bps = ctx.accounts.product.cancellation_fee_bps;
+    policy.nft_mint = ctx.accounts.policy_nft_mint.key();
     policy.status = PolicyStatus::Active;
-    policy.created_at = Clock::get()?.unix_timestamp;
+    policy.created_at = now;
     policy.bump = ctx.bumps.policy;
+
+    // Track outstanding liability
+    ctx.accounts.config.total_active_coverage = new_liability;
+    ctx.accounts.product.active_coverage = product_coverage;
+    ctx.accounts.product.active_policies = ctx.accounts.product.active_policies
+        .checked_add(1)
+        .ok_or(ZyuraError::MathOverflow)?;
+    ctx.accounts.flight.total_coverage = flight_coverage;
+    let flight_exposure = &mut ctx.accounts.flight_exposure;
+    flight_exposure.policyholder = ctx.accounts.user.key();
+    flight_exposure.flight = ctx.accounts.flight.key();
+    flight_exposure.coverage = holder_coverage;
+    flight_exposure.bump = ctx.bumps.flight_exposure;
     
     // Mint the policy NFT (1 token) to the user
     let mint_key = ctx.accounts.policy_nft_mint.key();
@@ -280,9 +744,11 @@ pub fn purchase_policy(
         policy_id,
         policyholder: ctx.accounts.user.key(),
         nft_mint: mint_key,
+        coverage_amount,
+        premium_paid: premium_amount,
     });
     msg!("Policy {} created. NFT minted: {}", policy_id, mint_key);
-    Ok(())
+    Ok(policy_id)
 }
 
 #[event]
@@ -290,33 +756,457 @@ pub struct PolicyPurchased {
     pub policy_id: u64,
     pub policyholder: Pubkey,
     pub nft_mint: Pubkey,
+    pub coverage_amount: u64,
+    pub premium_paid: u64,
 }
 
 pub fn process_payout(
     ctx: Context<ProcessPayout>,
     policy_id: u64,
-    delay_minutes: u32,
+    outcome: FlightOutcome,
 ) -> Result<()> {
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
     require!(ctx.accounts.policy.status == PolicyStatus::Active, ZyuraError::PolicyNotActive);
-    require!(delay_minutes >= ctx.accounts.product.delay_threshold_minutes, ZyuraError::DelayThresholdNotMet);
-    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    let payout_amount = ctx.accounts.policy.payout_for_outcome(&outcome)?;
+    ctx.accounts.config.require_role(Role::Settler, &ctx.accounts.admin.key())?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= claim_window_end(&ctx.accounts.policy)?, ZyuraError::ClaimWindowClosed);
+    record_flight_outcome(&mut ctx.accounts.flight, outcome, now, false)?;
     
-    // Transfer payout from risk pool to policyholder
-    let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
+    let accounts = ctx.accounts;
+    pay_claim(
+        &mut accounts.config,
+        &mut accounts.product,
+        &mut accounts.policy,
+        &accounts.risk_pool_vault,
+        &accounts.policyholder_usdc_account,
+        &accounts.token_program,
+        payout_amount,
+    )?;
+    
+    msg!("Payout processed for policy {} ({:?})", policy_id, outcome);
+    Ok(())
+}
+
+pub fn claim_with_oracle(
+    ctx: Context<ClaimWithOracle>,
+    policy_id: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
+    require!(ctx.accounts.policy.status == PolicyStatus::Active, ZyuraError::PolicyNotActive);
+    ctx.accounts.config.require_role(Role::Settler, &ctx.accounts.admin.key())?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= claim_window_end(&ctx.accounts.policy)?, ZyuraError::ClaimWindowClosed);
+    
+    // Delay comes from the Switchboard feed rather than an instruction argument
+    let delay_minutes = oracle::read_delay_minutes(
+        &ctx.accounts.oracle_feed.to_account_info(),
+        now,
+        ctx.accounts.config.oracle_max_staleness_secs,
+    )?;
+    let outcome = FlightOutcome::Delayed { minutes: delay_minutes };
+    let payout_amount = ctx.accounts.policy.payout_for_outcome(&outcome)?;
+    record_flight_outcome(&mut ctx.accounts.flight, outcome, now, false)?;
+    
+    let accounts = ctx.accounts;
+    pay_claim(
+        &mut accounts.config,
+        &mut accounts.product,
+        &mut accounts.policy,
+        &accounts.risk_pool_vault,
+        &accounts.policyholder_usdc_account,
+        &accounts.token_program,
+        payout_amount,
+    )?;
+    
+    msg!("Oracle payout processed for policy {} ({} min delay)", policy_id, delay_minutes);
+    Ok(())
+}
+
+pub fn claim_payout(
+    ctx: Context<ClaimPayout>,
+    policy_id: u64,
+    outcome: FlightOutcome,
+    observed_at: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
+    require!(ctx.accounts.policy.status == PolicyStatus::Active, ZyuraError::PolicyNotActive);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= claim_window_end(&ctx.accounts.policy)?, ZyuraError::ClaimWindowClosed);
+    
+    let oracle_authority = ctx.accounts.config.oracle_authority;
+    require!(oracle_authority != Pubkey::default(), ZyuraError::OracleAuthorityNotSet);
+    require!(observed_at <= now, ZyuraError::StaleOracleAttestation);
+    // A far-past `observed_at` overflows the age; treat it as stale rather than panicking
+    let age = now
+        .checked_sub(observed_at)
+        .ok_or(ZyuraError::StaleOracleAttestation)?;
+    require!(
+        age <= ctx.accounts.config.oracle_max_staleness_secs,
+        ZyuraError::StaleOracleAttestation
+    );
+    
+    // The oracle signs over the policy address, so an attestation cannot be replayed on another policy
+    let message = oracle::outcome_attestation_message(&ctx.accounts.policy.key(), &outcome, observed_at);
+    oracle::verify_ed25519_attestation(
+        &ctx.accounts.instructions_sysvar.to_account_info(),
+        &oracle_authority,
+        &message,
+    )?;
+    let payout_amount = ctx.accounts.policy.payout_for_outcome(&outcome)?;
+    record_flight_outcome(&mut ctx.accounts.flight, outcome, observed_at, false)?;
+    
+    let accounts = ctx.accounts;
+    pay_claim(
+        &mut accounts.config,
+        &mut accounts.product,
+        &mut accounts.policy,
+        &accounts.risk_pool_vault,
+        &accounts.policyholder_usdc_account,
+        &accounts.token_program,
+        payout_amount,
+    )?;
+    
+    msg!("Attested payout processed for policy {} ({:?})", policy_id, outcome);
+    Ok(())
+}
+
+/// Settle a batch of one product's policies on a flight against a single outcome.
+/// `remaining_accounts` holds (policy, policyholder USDC account) pairs, both writable.
+pub fn settle_flight<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleFlight<'info>>,
+    outcome: FlightOutcome,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
+    ctx.accounts.config.require_role(Role::Settler, &ctx.accounts.admin.key())?;
+    let remaining = ctx.remaining_accounts;
+    let pairs = remaining.chunks_exact(2);
+    require!(
+        !remaining.is_empty() && pairs.remainder().is_empty(),
+        ZyuraError::InvalidSettlementAccounts
+    );
+    
+    let now = Clock::get()?.unix_timestamp;
+    // Settlement is the finalization step: the flight's outcome can no longer change after it
+    record_flight_outcome(&mut ctx.accounts.flight, outcome, now, true)?;
+    let flight_key = ctx.accounts.flight.key();
+    let usdc_mint = ctx.accounts.config.usdc_mint;
+    
+    let accounts = ctx.accounts;
+    let mut paid_policies: u32 = 0;
+    let mut expired_policies: u32 = 0;
+    let mut total_paid: u64 = 0;
+    for pair in pairs {
+        require!(pair[0].is_writable, ZyuraError::InvalidSettlementAccounts);
+        let mut policy = Account::<Policy>::try_from(&pair[0])?;
+        require_keys_eq!(policy.flight, flight_key, ZyuraError::PolicyFlightMismatch);
+        require!(policy.product_id == accounts.product.id, ZyuraError::PolicyProductMismatch);
+        // Skip policies already settled, so an interrupted batch can simply be resubmitted
+        if policy.status != PolicyStatus::Active {
+            continue;
+        }
+        
+        let policyholder_usdc_account = Account::<TokenAccount>::try_from(&pair[1])?;
+        require_keys_eq!(policyholder_usdc_account.mint, usdc_mint, ZyuraError::InvalidUsdcMint);
+        require_keys_eq!(
+            policyholder_usdc_account.owner,
+            policy.policyholder,
+            ZyuraError::InvalidPolicyholderAccount
+        );
+        
+        // The flight's outcome is now final, so a policy it does not pay can never be claimed
+        if policy.covers(&outcome) && now <= claim_window_end(&policy)? {
+            let payout_amount = policy.payout_for_outcome(&outcome)?;
+            pay_claim(
+                &mut accounts.config,
+                &mut accounts.product,
+                &mut policy,
+                &accounts.risk_pool_vault,
+                &policyholder_usdc_account,
+                &accounts.token_program,
+                payout_amount,
+            )?;
+            paid_policies += 1;
+            total_paid = total_paid.checked_add(payout_amount).ok_or(ZyuraError::MathOverflow)?;
+        } else {
+            policy.status = PolicyStatus::Expired;
+            release_liability(&mut accounts.config, &mut accounts.product, policy.coverage_amount)?;
+            emit!(PolicyExpired {
+                policy_id: policy.id,
+                policyholder: policy.policyholder,
+            });
+            expired_policies += 1;
+        }
+        policy.exit(&crate::ID)?;
+    }
+    
+    emit!(FlightSettled {
+        flight: flight_key,
+        outcome,
+        paid_policies,
+        expired_policies,
+        total_paid,
+    });
+    msg!(
+        "Flight settled ({:?}): {} paid, {} expired, {} paid out",
+        outcome,
+        paid_policies,
+        expired_policies,
+        total_paid
+    );
+    Ok(())
+}
+
+#[event]
+pub struct FlightSettled {
+    pub flight: Pubkey,
+    pub outcome: FlightOutcome,
+    pub paid_policies: u32,
+    pub expired_policies: u32,
+    pub total_paid: u64,
+}
+
+/// Record `outcome` as the flight's outcome, or require it to be consistent with the one already recorded.
+/// Only a cancellation can be known before the scheduled departure. Until the outcome is final, a later report
+/// may replace a recorded delay (see `FlightOutcome::supersedes`); `finalize` locks the recorded outcome.
+fn record_flight_outcome(flight: &mut Flight, outcome: FlightOutcome, reported_at: i64, finalize: bool) -> Result<()> {
+    require!(
+        outcome == FlightOutcome::Cancelled || reported_at >= flight.scheduled_departure,
+        ZyuraError::FlightNotDeparted
+    );
+    match flight.outcome {
+        Some(recorded) if recorded == outcome => {}
+        Some(recorded) => require!(
+            !flight.outcome_final
+                && outcome.supersedes(&recorded)
+                && reported_at >= flight.outcome_reported_at,
+            ZyuraError::FlightOutcomeMismatch
+        ),
+        None => {}
+    }
+    if flight.outcome != Some(outcome) {
+        flight.outcome = Some(outcome);
+        flight.outcome_reported_at = reported_at;
+    }
+    if finalize || !matches!(outcome, FlightOutcome::Delayed { .. }) {
+        flight.outcome_final = true;
+    }
+    Ok(())
+}
+
+/// Pay `payout_amount` of a policy's coverage out of the vault, mark it PaidOut and release its liability.
+fn pay_claim<'info>(
+    config: &mut Account<'info, Config>,
+    product: &mut Account<'info, Product>,
+    policy: &mut Account<'info, Policy>,
+    risk_pool_vault: &Account<'info, TokenAccount>,
+    policyholder_usdc_account: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    payout_amount: u64,
+) -> Result<()> {
+    // Transfer payout from risk pool to policyholder, signed by the config PDA that owns the vault
+    let config_bump = config.bump;
+    let signer_seeds: &[&[u8]] = &[b"config", &[config_bump]];
+    let signer = &[signer_seeds];
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
         Transfer {
-            from: ctx.accounts.risk_pool_vault.to_account_info(),
-            to: ctx.accounts.policyholder_usdc_account.to_account_info(),
-            authority: ctx.accounts.admin.to_account_info(),
+            from: risk_pool_vault.to_account_info(),
+            to: policyholder_usdc_account.to_account_info(),
+            authority: config.to_account_info(),
         },
+        signer,
     );
-    token::transfer(transfer_ctx, ctx.accounts.policy.coverage_amount)?;
+    token::transfer(transfer_ctx, payout_amount)?;
     
     // Update policy status
-    ctx.accounts.policy.status = PolicyStatus::PaidOut;
-    ctx.accounts.policy.paid_at = Some(Clock::get()?.unix_timestamp);
+    policy.status = PolicyStatus::PaidOut;
+    policy.paid_at = Some(Clock::get()?.unix_timestamp);
+
+    // Release the policy's full liability, whichever band paid out
+    release_liability(config, product, policy.coverage_amount)?;
+    Ok(())
+}
+
+/// Remove a settled policy's coverage from the pool and product liability totals.
+/// Releasing more than is recorded means the totals have drifted, so it fails rather than clamping at zero.
+fn release_liability(config: &mut Config, product: &mut Product, coverage_amount: u64) -> Result<()> {
+    config.total_active_coverage = config
+        .total_active_coverage
+        .checked_sub(coverage_amount)
+        .ok_or(ZyuraError::LiabilityUnderflow)?;
+    product.active_coverage = product
+        .active_coverage
+        .checked_sub(coverage_amount)
+        .ok_or(ZyuraError::LiabilityUnderflow)?;
+    product.active_policies = product
+        .active_policies
+        .checked_sub(1)
+        .ok_or(ZyuraError::LiabilityUnderflow)?;
+    Ok(())
+}
+
+pub fn expire_policy(ctx: Context<ExpirePolicy>) -> Result<()> {
+    // Permissionless housekeeping: no funds move, so this is allowed while paused
+    require!(ctx.accounts.policy.status == PolicyStatus::Active, ZyuraError::PolicyNotActive);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > claim_window_end(&ctx.accounts.policy)?, ZyuraError::ClaimWindowOpen);
+    
+    ctx.accounts.policy.status = PolicyStatus::Expired;
+    
+    // Release the policy's liability
+    release_liability(&mut ctx.accounts.config, &mut ctx.accounts.product, ctx.accounts.policy.coverage_amount)?;
+    
+    emit!(PolicyExpired {
+        policy_id: ctx.accounts.policy.id,
+        policyholder: ctx.accounts.policy.policyholder,
+    });
+    msg!("Policy {} expired", ctx.accounts.policy.id);
+    Ok(())
+}
+
+#[event]
+pub struct PolicyExpired {
+    pub policy_id: u64,
+    pub policyholder: Pubkey,
+}
+
+pub fn cancel_policy(ctx: Context<CancelPolicy>) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
+    require!(ctx.accounts.policy.status == PolicyStatus::Active, ZyuraError::PolicyNotActive);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= cancellation_cutoff(&ctx.accounts.policy)?, ZyuraError::CancellationCutoffPassed);
+    
+    let premium_paid = ctx.accounts.policy.premium_paid;
+    let fee = ((premium_paid as u128 * ctx.accounts.policy.cancellation_fee_bps as u128) / 10_000u128) as u64;
+    let refund = premium_paid.checked_sub(fee).ok_or(ZyuraError::MathOverflow)?;
+    
+    // Refund from the vault, signed by the config PDA that owns it
+    if refund > 0 {
+        let config_bump = ctx.accounts.config.bump;
+        let signer_seeds: &[&[u8]] = &[b"config", &[config_bump]];
+        let signer = &[signer_seeds];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.risk_pool_vault.to_account_info(),
+                to: ctx.accounts.user_usdc_account.to_account_info(),
+                authority: ctx.accounts.config.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, refund)?;
+    }
+    
+    // The NFT ATA was frozen at purchase; thaw it so the holder can burn the NFT and reclaim the rent
+    let mint_bump = ctx.bumps.mint_authority;
+    let mint_signer_seeds: &[&[u8]] = &[b"policy_mint_authority", &[mint_bump]];
+    let mint_signer = &[mint_signer_seeds];
+    let thaw_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        ThawAccount {
+            account: ctx.accounts.user_policy_nft_ata.to_account_info(),
+            mint: ctx.accounts.policy_nft_mint.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        mint_signer,
+    );
+    token::thaw_account(thaw_ctx)?;
+    
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.policy_nft_mint.to_account_info(),
+            from: ctx.accounts.user_policy_nft_ata.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::burn(burn_ctx, ctx.accounts.user_policy_nft_ata.amount)?;
+    
+    let close_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.user_policy_nft_ata.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::close_account(close_ctx)?;
+    
+    ctx.accounts.policy.status = PolicyStatus::Cancelled;
+    
+    // Release the policy's liability and free its room under the flight caps for new buyers
+    let coverage_amount = ctx.accounts.policy.coverage_amount;
+    release_liability(&mut ctx.accounts.config, &mut ctx.accounts.product, coverage_amount)?;
+    ctx.accounts.flight.total_coverage = ctx
+        .accounts
+        .flight
+        .total_coverage
+        .checked_sub(coverage_amount)
+        .ok_or(ZyuraError::LiabilityUnderflow)?;
+    ctx.accounts.flight_exposure.coverage = ctx
+        .accounts
+        .flight_exposure
+        .coverage
+        .checked_sub(coverage_amount)
+        .ok_or(ZyuraError::LiabilityUnderflow)?;
     
-    msg!("Payout processed for policy {}", policy_id);
+    emit!(PolicyCancelled {
+        policy_id: ctx.accounts.policy.id,
+        policyholder: ctx.accounts.policy.policyholder,
+        refund,
+        fee,
+    });
+    msg!("Policy {} cancelled. Refunded {} (fee {})", ctx.accounts.policy.id, refund, fee);
     Ok(())
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct PolicyCancelled {
+    pub policy_id: u64,
+    pub policyholder: Pubkey,
+    pub refund: u64,
+    pub fee: u64,
+}
+
+/// Last unix timestamp at which `policy` may still be cancelled.
+fn cancellation_cutoff(policy: &Policy) -> Result<i64> {
+    let cutoff_secs = (policy.cancellation_cutoff_hours as i64)
+        .checked_mul(3600)
+        .ok_or(ZyuraError::MathOverflow)?;
+    policy.departure_time
+        .checked_sub(cutoff_secs)
+        .ok_or(error!(ZyuraError::MathOverflow))
+}
+
+/// Require `departure_time` to fall between the product's minimum lead time and booking horizon from `now`.
+fn check_sales_window(product: &Product, departure_time: i64, now: i64) -> Result<()> {
+    let lead_secs = departure_time
+        .checked_sub(now)
+        .ok_or(ZyuraError::MathOverflow)?;
+    require!(
+        lead_secs >= product.min_lead_time_minutes as i64 * 60,
+        ZyuraError::DepartureTooSoon
+    );
+    require!(
+        lead_secs <= product.max_booking_horizon_days as i64 * 86_400,
+        ZyuraError::DepartureTooFar
+    );
+    Ok(())
+}
+
+/// Whether `total` fits under `cap`, where a cap of 0 means uncapped.
+fn within_cap(total: u64, cap: u64) -> bool {
+    cap == 0 || total <= cap
+}
+
+/// Last unix timestamp at which a claim against `policy` is accepted.
+fn claim_window_end(policy: &Policy) -> Result<i64> {
+    let window_secs = (policy.claim_window_hours as i64)
+        .checked_mul(3600)
+        .ok_or(ZyuraError::MathOverflow)?;
+    policy.departure_time
+        .checked_add(window_secs)
+        .ok_or(error!(ZyuraError::MathOverflow))
+}
@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{Config, Product};
+use crate::state::{Config, DelayBand, PricingPoint, Product, ProductChange, Role};
+use crate::instructions::timelock;
 use crate::errors::ZyuraError;
 
 #[derive(Accounts)]
-#[instruction(product_id: u64)]
 pub struct CreateProduct<'info> {
     #[account(
         mut,
@@ -12,11 +12,13 @@ pub struct CreateProduct<'info> {
     )]
     pub config: Account<'info, Config>,
     
+    // Id comes from the config counter; an account already living at that PDA is rejected
+    // in the handler with `ProductAlreadyExists` rather than being overwritten
     #[account(
         init_if_needed,
         payer = admin,
         space = 8 + Product::INIT_SPACE,
-        seeds = [b"product", product_id.to_le_bytes().as_ref()],
+        seeds = [b"product", config.next_product_id.to_le_bytes().as_ref()],
         bump
     )]
     pub product: Account<'info, Product>,
@@ -27,6 +29,26 @@ pub struct CreateProduct<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SkipProductId<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    /// CHECK: only checked to be a program account holding data, so products written by an earlier program
+    /// version (whose layout no longer deserializes as `Product`) can be skipped too
+    #[account(
+        seeds = [b"product", config.next_product_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub product: UncheckedAccount<'info>,
+    
+    pub admin: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(product_id: u64)]
 pub struct UpdateProduct<'info> {
@@ -50,47 +72,417 @@ pub struct UpdateProduct<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SetCancellationTerms<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetDelayBands<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPricingCurve<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOutcomePayouts<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSalesWindow<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCoverageRange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCoverageCap<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProductActive<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProduct<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump,
+        close = admin
+    )]
+    pub product: Account<'info, Product>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
 pub fn create_product(
     ctx: Context<CreateProduct>,
-    product_id: u64,
     delay_threshold_minutes: u32,
-    coverage_amount: u64,
+    min_coverage: u64,
+    max_coverage: u64,
+    coverage_step: u64,
     premium_rate_bps: u16,
     claim_window_hours: u32,
 ) -> Result<()> {
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
-    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    Product::validate_coverage_range(min_coverage, max_coverage, coverage_step)?;
+    
+    // Ids start at 1, so a freshly created account still has id 0
+    require!(ctx.accounts.product.id == 0, ZyuraError::ProductAlreadyExists);
+    let product_id = ctx.accounts.config.next_product_id;
+    ctx.accounts.config.next_product_id = product_id
+        .checked_add(1)
+        .ok_or(ZyuraError::MathOverflow)?;
     
     let product = &mut ctx.accounts.product;
     product.id = product_id;
     product.delay_threshold_minutes = delay_threshold_minutes;
-    product.coverage_amount = coverage_amount;
+    product.min_coverage = min_coverage;
+    product.max_coverage = max_coverage;
+    product.coverage_step = coverage_step;
     product.premium_rate_bps = premium_rate_bps;
     product.claim_window_hours = claim_window_hours;
+    product.delay_bands = Vec::new();
+    product.pricing_curve = Vec::new();
+    product.flight_cancelled_payout_bps = 0;
+    product.flight_diverted_payout_bps = 0;
+    product.cancellation_cutoff_hours = Product::DEFAULT_CANCELLATION_CUTOFF_HOURS;
+    product.cancellation_fee_bps = Product::DEFAULT_CANCELLATION_FEE_BPS;
+    product.min_lead_time_minutes = Product::DEFAULT_MIN_LEAD_TIME_MINUTES;
+    product.max_booking_horizon_days = Product::DEFAULT_MAX_BOOKING_HORIZON_DAYS;
+    product.max_active_coverage = 0;
+    product.pending_change = None;
+    product.active_coverage = 0;
+    product.active_policies = 0;
     product.active = true;
     product.bump = ctx.bumps.product;
     
+    emit!(ProductCreated { product_id });
     msg!("Product {} created", product_id);
     Ok(())
 }
 
+#[event]
+pub struct ProductCreated {
+    pub product_id: u64,
+}
+
+/// Advance the product counter past an id whose PDA already holds a product. A config that was
+/// closed and re-initialized restarts its counter at 1 while the old products survive, and
+/// `create_product` rejects those ids with `ProductAlreadyExists` until they are skipped.
+pub fn skip_product_id(ctx: Context<SkipProductId>) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    let product = &ctx.accounts.product;
+    require!(
+        product.owner == &crate::ID && !product.data_is_empty(),
+        ZyuraError::ProductIdNotTaken
+    );
+    
+    let skipped = ctx.accounts.config.next_product_id;
+    ctx.accounts.config.next_product_id = skipped
+        .checked_add(1)
+        .ok_or(ZyuraError::MathOverflow)?;
+    
+    msg!("Product id {} is taken by {}, skipped", skipped, ctx.accounts.product.key());
+    Ok(())
+}
+
 pub fn update_product(
     ctx: Context<UpdateProduct>,
     id: u64,
     delay_threshold_minutes: u32,
-    coverage_amount: u64,
     premium_rate_bps: u16,
     claim_window_hours: u32,
 ) -> Result<()> {
     require!(!ctx.accounts.config.paused, ZyuraError::ProtocolPaused);
-    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+
+    // Applied by `execute_product_change` once the timelock delay has passed
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::Terms {
+            delay_threshold_minutes,
+            premium_rate_bps,
+            claim_window_hours,
+        },
+    )?;
+
+    msg!("Product {} update queued", id);
+    Ok(())
+}
+
+pub fn set_cancellation_terms(
+    ctx: Context<SetCancellationTerms>,
+    cancellation_cutoff_hours: u32,
+    cancellation_fee_bps: u16,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    require!(cancellation_fee_bps <= 10_000, ZyuraError::InvalidCancellationFee);
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::CancellationTerms {
+            cancellation_cutoff_hours,
+            cancellation_fee_bps,
+        },
+    )
+}
+
+pub fn set_delay_bands(ctx: Context<SetDelayBands>, delay_bands: Vec<DelayBand>) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    require!(delay_bands.len() <= Product::MAX_DELAY_BANDS, ZyuraError::InvalidDelayBands);
+    for band in &delay_bands {
+        require!(band.payout_bps > 0 && band.payout_bps <= 10_000, ZyuraError::InvalidDelayBands);
+    }
+    // Longer delays must never pay less than shorter ones
+    for pair in delay_bands.windows(2) {
+        require!(
+            pair[1].min_delay_minutes > pair[0].min_delay_minutes && pair[1].payout_bps >= pair[0].payout_bps,
+            ZyuraError::InvalidDelayBands
+        );
+    }
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::DelayBands { delay_bands },
+    )
+}
+
+pub fn set_pricing_curve(ctx: Context<SetPricingCurve>, pricing_curve: Vec<PricingPoint>) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    require!(pricing_curve.len() <= Product::MAX_PRICING_POINTS, ZyuraError::InvalidPricingCurve);
+    for point in &pricing_curve {
+        require!(point.premium_multiplier_bps >= 10_000, ZyuraError::InvalidPricingCurve);
+    }
+    // Premiums may only rise as the pool fills up
+    for pair in pricing_curve.windows(2) {
+        require!(
+            pair[1].utilization_bps > pair[0].utilization_bps
+                && pair[1].premium_multiplier_bps >= pair[0].premium_multiplier_bps,
+            ZyuraError::InvalidPricingCurve
+        );
+    }
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::PricingCurve { pricing_curve },
+    )
+}
+
+pub fn set_outcome_payouts(
+    ctx: Context<SetOutcomePayouts>,
+    flight_cancelled_payout_bps: u16,
+    flight_diverted_payout_bps: u16,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    require!(
+        flight_cancelled_payout_bps <= 10_000 && flight_diverted_payout_bps <= 10_000,
+        ZyuraError::InvalidOutcomePayout
+    );
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::OutcomePayouts {
+            flight_cancelled_payout_bps,
+            flight_diverted_payout_bps,
+        },
+    )
+}
+
+pub fn set_sales_window(
+    ctx: Context<SetSalesWindow>,
+    min_lead_time_minutes: u32,
+    max_booking_horizon_days: u32,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    require!(
+        max_booking_horizon_days > 0
+            && (max_booking_horizon_days as u64) * 24 * 60 > min_lead_time_minutes as u64,
+        ZyuraError::InvalidSalesWindow
+    );
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::SalesWindow {
+            min_lead_time_minutes,
+            max_booking_horizon_days,
+        },
+    )
+}
+
+pub fn set_coverage_range(
+    ctx: Context<SetCoverageRange>,
+    min_coverage: u64,
+    max_coverage: u64,
+    coverage_step: u64,
+) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+    Product::validate_coverage_range(min_coverage, max_coverage, coverage_step)?;
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::CoverageRange {
+            min_coverage,
+            max_coverage,
+            coverage_step,
+        },
+    )
+}
+
+pub fn set_coverage_cap(ctx: Context<SetCoverageCap>, max_active_coverage: u64) -> Result<()> {
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
+
+    timelock::queue_product_change(
+        &ctx.accounts.config,
+        &mut ctx.accounts.product,
+        ProductChange::CoverageCap { max_active_coverage },
+    )
+}
+
+pub fn set_product_active(ctx: Context<SetProductActive>, active: bool) -> Result<()> {
+    // Not timelocked: stopping sales of a mispriced product must take effect immediately
+    ctx.accounts.config.require_role(Role::ProductManager, &ctx.accounts.admin.key())?;
 
     let product = &mut ctx.accounts.product;
-    product.delay_threshold_minutes = delay_threshold_minutes;
-    product.coverage_amount = coverage_amount;
-    product.premium_rate_bps = premium_rate_bps;
-    product.claim_window_hours = claim_window_hours;
+    product.active = active;
+
+    msg!("Product {} active status set to: {}", product.id, active);
+    Ok(())
+}
+
+pub fn close_product(ctx: Context<CloseProduct>) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    require!(ctx.accounts.product.active_policies == 0, ZyuraError::ProductHasActivePolicies);
 
-    msg!("Product {} updated", id);
+    msg!("Product {} closed", ctx.accounts.product.id);
     Ok(())
 }
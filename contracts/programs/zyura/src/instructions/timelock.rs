@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use crate::state::{Config, ConfigChange, PendingConfigChange, PendingProductChange, Product, ProductChange};
+use crate::errors::ZyuraError;
+
+#[derive(Accounts)]
+pub struct ExecuteProductChange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProductChange<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    #[account(
+        mut,
+        seeds = [b"product", product.id.to_le_bytes().as_ref()],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+    
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+    
+    pub admin: Signer<'info>,
+}
+
+/// Queue `change` on `product`; it can be executed once the configured timelock delay has passed.
+pub fn queue_product_change(config: &Config, product: &mut Product, change: ProductChange) -> Result<()> {
+    require!(product.pending_change.is_none(), ZyuraError::ChangeAlreadyPending);
+    let eta = eta_after_delay(config)?;
+    product.pending_change = Some(PendingProductChange { change: change.clone(), eta });
+    
+    emit!(ProductChangeQueued {
+        product_id: product.id,
+        change,
+        eta,
+    });
+    msg!("Product {} change queued, executable at {}", product.id, eta);
+    Ok(())
+}
+
+/// Queue `change` on `config`; it can be executed once the configured timelock delay has passed.
+pub fn queue_config_change(config: &mut Config, change: ConfigChange) -> Result<()> {
+    require!(config.pending_change.is_none(), ZyuraError::ChangeAlreadyPending);
+    let eta = eta_after_delay(config)?;
+    config.pending_change = Some(PendingConfigChange { change, eta });
+    
+    emit!(ConfigChangeQueued { change, eta });
+    msg!("Config change queued, executable at {}", eta);
+    Ok(())
+}
+
+fn eta_after_delay(config: &Config) -> Result<i64> {
+    Clock::get()?
+        .unix_timestamp
+        .checked_add(config.timelock_delay_secs)
+        .ok_or(error!(ZyuraError::MathOverflow))
+}
+
+pub fn execute_product_change(ctx: Context<ExecuteProductChange>) -> Result<()> {
+    // Permissionless: the change was authorized when it was queued
+    let product = &mut ctx.accounts.product;
+    let pending = product.pending_change.take().ok_or(ZyuraError::NoPendingChange)?;
+    require!(Clock::get()?.unix_timestamp >= pending.eta, ZyuraError::TimelockNotElapsed);
+    
+    match pending.change.clone() {
+        ProductChange::Terms {
+            delay_threshold_minutes,
+            premium_rate_bps,
+            claim_window_hours,
+        } => {
+            product.delay_threshold_minutes = delay_threshold_minutes;
+            product.premium_rate_bps = premium_rate_bps;
+            product.claim_window_hours = claim_window_hours;
+        }
+        ProductChange::CancellationTerms {
+            cancellation_cutoff_hours,
+            cancellation_fee_bps,
+        } => {
+            product.cancellation_cutoff_hours = cancellation_cutoff_hours;
+            product.cancellation_fee_bps = cancellation_fee_bps;
+        }
+        ProductChange::DelayBands { delay_bands } => {
+            product.delay_bands = delay_bands;
+        }
+        ProductChange::OutcomePayouts {
+            flight_cancelled_payout_bps,
+            flight_diverted_payout_bps,
+        } => {
+            product.flight_cancelled_payout_bps = flight_cancelled_payout_bps;
+            product.flight_diverted_payout_bps = flight_diverted_payout_bps;
+        }
+        ProductChange::SalesWindow {
+            min_lead_time_minutes,
+            max_booking_horizon_days,
+        } => {
+            product.min_lead_time_minutes = min_lead_time_minutes;
+            product.max_booking_horizon_days = max_booking_horizon_days;
+        }
+        ProductChange::CoverageCap { max_active_coverage } => {
+            product.max_active_coverage = max_active_coverage;
+        }
+        ProductChange::CoverageRange {
+            min_coverage,
+            max_coverage,
+            coverage_step,
+        } => {
+            product.min_coverage = min_coverage;
+            product.max_coverage = max_coverage;
+            product.coverage_step = coverage_step;
+        }
+        ProductChange::PricingCurve { pricing_curve } => {
+            product.pricing_curve = pricing_curve;
+        }
+    }
+    
+    emit!(ProductChangeExecuted {
+        product_id: product.id,
+        change: pending.change,
+    });
+    msg!("Product {} change executed", product.id);
+    Ok(())
+}
+
+pub fn cancel_product_change(ctx: Context<CancelProductChange>) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    
+    let product = &mut ctx.accounts.product;
+    let pending = product.pending_change.take().ok_or(ZyuraError::NoPendingChange)?;
+    
+    emit!(ProductChangeCancelled {
+        product_id: product.id,
+        change: pending.change,
+    });
+    msg!("Product {} change cancelled", product.id);
+    Ok(())
+}
+
+pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    // Permissionless: the change was authorized when it was queued
+    let config = &mut ctx.accounts.config;
+    let pending = config.pending_change.take().ok_or(ZyuraError::NoPendingChange)?;
+    require!(Clock::get()?.unix_timestamp >= pending.eta, ZyuraError::TimelockNotElapsed);
+    
+    match pending.change {
+        ConfigChange::ReserveRatio { reserve_ratio_bps } => {
+            config.reserve_ratio_bps = reserve_ratio_bps;
+        }
+        ConfigChange::OracleMaxStaleness { max_staleness_secs } => {
+            config.oracle_max_staleness_secs = max_staleness_secs;
+        }
+        ConfigChange::OracleAuthority { oracle_authority } => {
+            config.oracle_authority = oracle_authority;
+        }
+        ConfigChange::TimelockDelay { timelock_delay_secs } => {
+            config.timelock_delay_secs = timelock_delay_secs;
+        }
+        ConfigChange::ExposureCaps {
+            max_flight_coverage,
+            max_policyholder_flight_coverage,
+        } => {
+            config.max_flight_coverage = max_flight_coverage;
+            config.max_policyholder_flight_coverage = max_policyholder_flight_coverage;
+        }
+    }
+    
+    emit!(ConfigChangeExecuted { change: pending.change });
+    msg!("Config change executed");
+    Ok(())
+}
+
+pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+    require!(ctx.accounts.config.admin == ctx.accounts.admin.key(), ZyuraError::Unauthorized);
+    
+    let pending = ctx.accounts.config.pending_change.take().ok_or(ZyuraError::NoPendingChange)?;
+    
+    emit!(ConfigChangeCancelled { change: pending.change });
+    msg!("Config change cancelled");
+    Ok(())
+}
+
+#[event]
+pub struct ProductChangeQueued {
+    pub product_id: u64,
+    pub change: ProductChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ProductChangeExecuted {
+    pub product_id: u64,
+    pub change: ProductChange,
+}
+
+#[event]
+pub struct ProductChangeCancelled {
+    pub product_id: u64,
+    pub change: ProductChange,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub change: ConfigChange,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub change: ConfigChange,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub change: ConfigChange,
+}
@@ -1,23 +1,33 @@
-use anchor_lang::prelude::*;
+//! ZYURA – On-chain flight-delay insurance
+//!
+//! Workflow overview:
+//! - Admin initializes config and creates one or more `Product`s (coverage, premium rate, thresholds).
+//! - Liquidity providers deposit USDC into the protocol’s risk pool vault and receive LP shares
+//!   priced at pool NAV; shares are burned on withdrawal for their pro-rata USDC.
+//! - A customer purchases a policy by paying the premium in USDC:
+//!   - Premium is transferred to the risk pool vault
+//!   - A `Policy` account is created and set Active
+//!   - An NFT is minted to the buyer as proof-of-insurance (Metaplex-compliant, supply=1)
+//! - When an eligible delay, cancellation or diversion is reported by admin/oracle, a payout is processed:
+//!   - USDC is transferred from the risk pool vault to the policyholder
+//!   - Policy status is updated to PaidOut
+//! - Before the product's cancellation cutoff, a policyholder may cancel: the premium less the
+//!   cancellation fee is refunded and the policy NFT is burned.
+
+// Anchor 0.31 `#[program]` expands to the deprecated `AccountInfo::realloc` in its IDL handlers,
+// and its macros test `cfg`s (`anchor-debug`, `custom-heap`, `target_os = "solana"`) this crate does not declare
+#![allow(deprecated, unexpected_cfgs)]
 
-/// ZYURA – On-chain flight-delay insurance
-///
-/// Workflow overview:
-/// - Admin initializes config and creates one or more `Product`s (coverage, premium rate, thresholds).
-/// - Liquidity providers deposit USDC into the protocol’s risk pool vault.
-/// - A customer purchases a policy by paying the premium in USDC:
-///   - Premium is transferred to the risk pool vault
-///   - A `Policy` account is created and set Active
-///   - An NFT is minted to the buyer as proof-of-insurance (Metaplex-compliant, supply=1)
-/// - When an eligible delay occurs and is authorized by admin/oracle policy, a payout is processed:
-///   - USDC is transferred from the risk pool vault to the policyholder
-///   - Policy status is updated to PaidOut
+use anchor_lang::prelude::*;
 
 pub mod state;
 pub mod instructions;
 pub mod errors;
+pub mod oracle;
+pub mod flight;
 
 use instructions::*;
+use state::{DelayBand, FlightOutcome, PricingPoint, Role};
 
 declare_id!("DWErB1gSbiBBeEaXzy3KEsCbMZCD6sXmrVT9WF9mZgxX");
 
@@ -25,7 +35,7 @@ declare_id!("DWErB1gSbiBBeEaXzy3KEsCbMZCD6sXmrVT9WF9mZgxX");
 pub mod zyura {
     use super::*;
 
-    /// Initialize the ZYURA protocol
+    /// Initialize the ZYURA protocol (program upgrade authority only)
     pub fn initialize(
         ctx: Context<Initialize>,
         admin: Pubkey,
@@ -35,31 +45,37 @@ pub mod zyura {
         initialize::initialize(ctx, admin, usdc_mint, switchboard_program)
     }
 
-    /// Create a flight delay insurance product
+    /// Create a flight delay insurance product under the next id from the config counter (product manager)
     pub fn create_product(
         ctx: Context<CreateProduct>,
-        product_id: u64,
         delay_threshold_minutes: u32,
-        coverage_amount: u64,
+        min_coverage: u64,
+        max_coverage: u64,
+        coverage_step: u64,
         premium_rate_bps: u16,
         claim_window_hours: u32,
     ) -> Result<()> {
         product::create_product(
             ctx,
-            product_id,
             delay_threshold_minutes,
-            coverage_amount,
+            min_coverage,
+            max_coverage,
+            coverage_step,
             premium_rate_bps,
             claim_window_hours,
         )
     }
 
-    /// Update flight delay insurance product
+    /// Skip a product id left occupied by a product from before the config was re-initialized (product manager)
+    pub fn skip_product_id(ctx: Context<SkipProductId>) -> Result<()> {
+        product::skip_product_id(ctx)
+    }
+
+    /// Queue new terms for a flight delay insurance product (product manager, timelocked)
     pub fn update_product(
         ctx: Context<UpdateProduct>,
         id: u64,
         delay_threshold_minutes: u32,
-        coverage_amount: u64,
         premium_rate_bps: u16,
         claim_window_hours: u32,
     ) -> Result<()> {
@@ -67,32 +83,155 @@ pub mod zyura {
             ctx,
             id,
             delay_threshold_minutes,
-            coverage_amount,
             premium_rate_bps,
             claim_window_hours,
         )
     }
 
-    /// Purchase flight delay insurance policy
+    /// Queue a product's cancellation cutoff and fee (product manager, timelocked)
+    pub fn set_cancellation_terms(
+        ctx: Context<SetCancellationTerms>,
+        cancellation_cutoff_hours: u32,
+        cancellation_fee_bps: u16,
+    ) -> Result<()> {
+        product::set_cancellation_terms(ctx, cancellation_cutoff_hours, cancellation_fee_bps)
+    }
+
+    /// Queue a product's tiered payout schedule; an empty list restores the single threshold (product manager, timelocked)
+    pub fn set_delay_bands(ctx: Context<SetDelayBands>, delay_bands: Vec<DelayBand>) -> Result<()> {
+        product::set_delay_bands(ctx, delay_bands)
+    }
+
+    /// Queue a product's premium multipliers by pool utilization; an empty list restores flat pricing (product manager, timelocked)
+    pub fn set_pricing_curve(ctx: Context<SetPricingCurve>, pricing_curve: Vec<PricingPoint>) -> Result<()> {
+        product::set_pricing_curve(ctx, pricing_curve)
+    }
+
+    /// Queue the share of coverage a product pays for flight cancellations and diversions (product manager, timelocked)
+    pub fn set_outcome_payouts(
+        ctx: Context<SetOutcomePayouts>,
+        flight_cancelled_payout_bps: u16,
+        flight_diverted_payout_bps: u16,
+    ) -> Result<()> {
+        product::set_outcome_payouts(ctx, flight_cancelled_payout_bps, flight_diverted_payout_bps)
+    }
+
+    /// Queue how close to and how far ahead of departure a product's policies can be bought (product manager, timelocked)
+    pub fn set_sales_window(
+        ctx: Context<SetSalesWindow>,
+        min_lead_time_minutes: u32,
+        max_booking_horizon_days: u32,
+    ) -> Result<()> {
+        product::set_sales_window(ctx, min_lead_time_minutes, max_booking_horizon_days)
+    }
+
+    /// Queue the range and step of coverage buyers can choose from (product manager, timelocked)
+    pub fn set_coverage_range(
+        ctx: Context<SetCoverageRange>,
+        min_coverage: u64,
+        max_coverage: u64,
+        coverage_step: u64,
+    ) -> Result<()> {
+        product::set_coverage_range(ctx, min_coverage, max_coverage, coverage_step)
+    }
+
+    /// Queue the cap on a product's outstanding coverage; 0 removes it (product manager, timelocked)
+    pub fn set_coverage_cap(ctx: Context<SetCoverageCap>, max_active_coverage: u64) -> Result<()> {
+        product::set_coverage_cap(ctx, max_active_coverage)
+    }
+
+    /// Stop or resume policy sales for a product (product manager)
+    pub fn set_product_active(ctx: Context<SetProductActive>, active: bool) -> Result<()> {
+        product::set_product_active(ctx, active)
+    }
+
+    /// Close a product with no Active policies and return its rent to the admin (admin only)
+    pub fn close_product(ctx: Context<CloseProduct>) -> Result<()> {
+        product::close_product(ctx)
+    }
+
+    /// Premium `purchase_policy` would currently charge for `coverage_amount` of a product (read-only)
+    pub fn quote_premium(ctx: Context<QuotePremium>, coverage_amount: u64) -> Result<u64> {
+        policy::quote_premium(ctx, coverage_amount)
+    }
+
+    /// Register a flight's scheduled departure, and the Switchboard feed (or the default pubkey for none)
+    /// oracle claims must read, so policies can be sold on it (settler)
+    pub fn register_flight(
+        ctx: Context<RegisterFlight>,
+        flight_number: String,
+        departure_time: i64,
+        oracle_feed: Pubkey,
+    ) -> Result<()> {
+        policy::register_flight(ctx, flight_number, departure_time, oracle_feed)
+    }
+
+    /// Purchase `coverage_amount` of flight delay insurance at the quoted premium, failing if it exceeds
+    /// `max_premium`; returns the id allocated from the buyer's policy counter
     pub fn purchase_policy(
         ctx: Context<PurchasePolicy>,
-        policy_id: u64,
         flight_number: String,
         departure_time: i64,
-        premium_amount: u64,
+        coverage_amount: u64,
+        max_premium: u64,
         create_metadata: bool,
         metadata_uri: String,
-    ) -> Result<()> {
-        policy::purchase_policy(ctx, policy_id, flight_number, departure_time, premium_amount, create_metadata, metadata_uri)
+    ) -> Result<u64> {
+        policy::purchase_policy(
+            ctx,
+            flight_number,
+            departure_time,
+            coverage_amount,
+            max_premium,
+            create_metadata,
+            metadata_uri,
+        )
     }
 
-    /// Process flight delay payout
+    /// Process payout for a flight outcome reported by the settler
     pub fn process_payout(
         ctx: Context<ProcessPayout>,
         policy_id: u64,
-        delay_minutes: u32,
+        outcome: FlightOutcome,
     ) -> Result<()> {
-        policy::process_payout(ctx, policy_id, delay_minutes)
+        policy::process_payout(ctx, policy_id, outcome)
+    }
+
+    /// Process flight delay payout using the delay reported by a Switchboard feed (settler)
+    pub fn claim_with_oracle(
+        ctx: Context<ClaimWithOracle>,
+        policy_id: u64,
+    ) -> Result<()> {
+        policy::claim_with_oracle(ctx, policy_id)
+    }
+
+    /// Claim a payout with a flight outcome attestation signed by the oracle authority (callable by anyone)
+    pub fn claim_payout(
+        ctx: Context<ClaimPayout>,
+        policy_id: u64,
+        outcome: FlightOutcome,
+        observed_at: i64,
+    ) -> Result<()> {
+        policy::claim_payout(ctx, policy_id, outcome, observed_at)
+    }
+
+    /// Record a flight's outcome and pay or expire a batch of one product's policies on it, passed as
+    /// (policy, policyholder token account) pairs in remaining accounts (settler)
+    pub fn settle_flight<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleFlight<'info>>,
+        outcome: FlightOutcome,
+    ) -> Result<()> {
+        policy::settle_flight(ctx, outcome)
+    }
+
+    /// Expire an Active policy once its claim window has passed (callable by anyone)
+    pub fn expire_policy(ctx: Context<ExpirePolicy>) -> Result<()> {
+        policy::expire_policy(ctx)
+    }
+
+    /// Cancel an Active policy before its cancellation cutoff and refund the premium less the fee
+    pub fn cancel_policy(ctx: Context<CancelPolicy>) -> Result<()> {
+        policy::cancel_policy(ctx)
     }
 
     /// Deposit liquidity into the risk pool
@@ -103,21 +242,90 @@ pub mod zyura {
         liquidity::deposit_liquidity(ctx, amount)
     }
 
-    /// Withdraw liquidity from the risk pool
+    /// Withdraw liquidity from the risk pool by redeeming LP shares
     pub fn withdraw_liquidity(
         ctx: Context<WithdrawLiquidity>,
-        amount: u64,
+        shares: u64,
     ) -> Result<()> {
-        liquidity::withdraw_liquidity(ctx, amount)
+        liquidity::withdraw_liquidity(ctx, shares)
     }
 
-    /// Set protocol pause status (admin only)
+    /// Set protocol pause status (pauser)
     pub fn set_pause_status(ctx: Context<SetPauseStatus>, paused: bool) -> Result<()> {
         admin::set_pause_status(ctx, paused)
     }
 
-    /// Close Config account (admin only) - used for migration
-    pub fn close_config(ctx: Context<CloseConfig>) -> Result<()> {
-        admin::close_config(ctx)
+    /// Queue the share of outstanding coverage the vault must hold (treasury, timelocked)
+    pub fn set_reserve_ratio(ctx: Context<SetReserveRatio>, reserve_ratio_bps: u16) -> Result<()> {
+        admin::set_reserve_ratio(ctx, reserve_ratio_bps)
+    }
+
+    /// Queue the caps on coverage sold per flight and per policyholder per flight; 0 removes a cap (treasury, timelocked)
+    pub fn set_exposure_caps(
+        ctx: Context<SetExposureCaps>,
+        max_flight_coverage: u64,
+        max_policyholder_flight_coverage: u64,
+    ) -> Result<()> {
+        admin::set_exposure_caps(ctx, max_flight_coverage, max_policyholder_flight_coverage)
+    }
+
+    /// Queue the maximum age of oracle data accepted for claims (admin only, timelocked)
+    pub fn set_oracle_max_staleness(ctx: Context<SetOracleMaxStaleness>, max_staleness_secs: i64) -> Result<()> {
+        admin::set_oracle_max_staleness(ctx, max_staleness_secs)
+    }
+
+    /// Queue the oracle key whose signed outcome attestations can be claimed against (admin only, timelocked)
+    pub fn set_oracle_authority(ctx: Context<SetOracleAuthority>, oracle_authority: Pubkey) -> Result<()> {
+        admin::set_oracle_authority(ctx, oracle_authority)
+    }
+
+    /// Queue a new timelock delay for product and config changes (admin only, timelocked)
+    pub fn set_timelock_delay(ctx: Context<SetTimelockDelay>, timelock_delay_secs: i64) -> Result<()> {
+        admin::set_timelock_delay(ctx, timelock_delay_secs)
+    }
+
+    /// Apply a product's queued change once its eta has passed (callable by anyone)
+    pub fn execute_product_change(ctx: Context<ExecuteProductChange>) -> Result<()> {
+        timelock::execute_product_change(ctx)
+    }
+
+    /// Drop a product's queued change (admin only)
+    pub fn cancel_product_change(ctx: Context<CancelProductChange>) -> Result<()> {
+        timelock::cancel_product_change(ctx)
+    }
+
+    /// Apply the queued config change once its eta has passed (callable by anyone)
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        timelock::execute_config_change(ctx)
+    }
+
+    /// Drop the queued config change (admin only)
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        timelock::cancel_config_change(ctx)
+    }
+
+    /// Assign a delegated role to `member`, replacing any previous holder (admin only)
+    pub fn grant_role(ctx: Context<UpdateRole>, role: Role, member: Pubkey) -> Result<()> {
+        admin::grant_role(ctx, role, member)
+    }
+
+    /// Clear the holder of a delegated role (admin only)
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+        admin::revoke_role(ctx, role)
+    }
+
+    /// Nominate a new admin; takes effect once they call `accept_admin` (admin only)
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        admin::propose_admin(ctx, new_admin)
+    }
+
+    /// Become admin as the nominee of a pending transfer
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        admin::accept_admin(ctx)
+    }
+
+    /// Withdraw a pending admin nomination (admin only)
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        admin::cancel_admin_transfer(ctx)
     }
 }
\ No newline at end of file
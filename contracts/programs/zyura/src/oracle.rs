@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::errors::ZyuraError;
+use crate::state::FlightOutcome;
+
+/// Anchor discriminator of Switchboard V2 `AggregatorAccountData`
+pub const AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+
+// Byte offsets into the packed `AggregatorAccountData` layout (discriminator included)
+// for the fields of `latest_confirmed_round` read here.
+const LATEST_ROUND_NUM_SUCCESS_OFFSET: usize = 341;
+const LATEST_ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+const LATEST_ROUND_RESULT_MANTISSA_OFFSET: usize = 366;
+const LATEST_ROUND_RESULT_SCALE_OFFSET: usize = 382;
+const LATEST_ROUND_MIN_LEN: usize = LATEST_ROUND_RESULT_SCALE_OFFSET + 4;
+
+/// Latest confirmed value reported by a Switchboard aggregator feed
+pub struct FeedReading {
+    pub mantissa: i128,
+    pub scale: u32,
+    pub timestamp: i64,
+}
+
+/// Decode the latest confirmed round of a Switchboard V2 aggregator.
+/// The caller is responsible for checking the account owner.
+pub fn read_aggregator(feed: &AccountInfo) -> Result<FeedReading> {
+    let data = feed.try_borrow_data()?;
+    require!(data.len() >= LATEST_ROUND_MIN_LEN, ZyuraError::InvalidOracleFeed);
+    require!(data[..8] == AGGREGATOR_DISCRIMINATOR, ZyuraError::InvalidOracleFeed);
+
+    let num_success = u32::from_le_bytes(
+        data[LATEST_ROUND_NUM_SUCCESS_OFFSET..LATEST_ROUND_NUM_SUCCESS_OFFSET + 4]
+            .try_into()
+            .unwrap(),
+    );
+    require!(num_success > 0, ZyuraError::InvalidOracleFeed);
+
+    let timestamp = i64::from_le_bytes(
+        data[LATEST_ROUND_OPEN_TIMESTAMP_OFFSET..LATEST_ROUND_OPEN_TIMESTAMP_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    );
+    let mantissa = i128::from_le_bytes(
+        data[LATEST_ROUND_RESULT_MANTISSA_OFFSET..LATEST_ROUND_RESULT_MANTISSA_OFFSET + 16]
+            .try_into()
+            .unwrap(),
+    );
+    let scale = u32::from_le_bytes(
+        data[LATEST_ROUND_RESULT_SCALE_OFFSET..LATEST_ROUND_RESULT_SCALE_OFFSET + 4]
+            .try_into()
+            .unwrap(),
+    );
+
+    Ok(FeedReading { mantissa, scale, timestamp })
+}
+
+/// Read a flight delay (in whole minutes) from a Switchboard feed, rejecting
+/// rounds older than `max_staleness_secs` or timestamped in the future.
+pub fn read_delay_minutes(feed: &AccountInfo, now: i64, max_staleness_secs: i64) -> Result<u32> {
+    let reading = read_aggregator(feed)?;
+    require!(reading.timestamp <= now, ZyuraError::StaleOracleFeed);
+    let age = now
+        .checked_sub(reading.timestamp)
+        .ok_or(ZyuraError::StaleOracleFeed)?;
+    require!(age <= max_staleness_secs, ZyuraError::StaleOracleFeed);
+    require!(reading.mantissa >= 0, ZyuraError::InvalidOracleFeed);
+
+    let divisor = 10i128
+        .checked_pow(reading.scale)
+        .ok_or(ZyuraError::InvalidOracleFeed)?;
+    u32::try_from(reading.mantissa / divisor).map_err(|_| error!(ZyuraError::InvalidOracleFeed))
+}
+
+/// Domain separator prefixed to every outcome attestation signed by the oracle key
+pub const OUTCOME_ATTESTATION_DOMAIN: &[u8] = b"ZYURA_OUTCOME_V1";
+
+// Layout of the native Ed25519 program instruction data
+const ED25519_HEADER_LEN: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+
+/// Message the oracle signs to attest `outcome` for `policy`, observed at `observed_at`:
+/// domain || policy || outcome tag (u8) || delay minutes (u32 LE, 0 unless delayed) || observed_at (i64 LE)
+pub fn outcome_attestation_message(policy: &Pubkey, outcome: &FlightOutcome, observed_at: i64) -> Vec<u8> {
+    let (tag, delay_minutes): (u8, u32) = match *outcome {
+        FlightOutcome::OnTime => (0, 0),
+        FlightOutcome::Delayed { minutes } => (1, minutes),
+        FlightOutcome::Cancelled => (2, 0),
+        FlightOutcome::Diverted => (3, 0),
+    };
+    let mut message = Vec::with_capacity(OUTCOME_ATTESTATION_DOMAIN.len() + 32 + 1 + 4 + 8);
+    message.extend_from_slice(OUTCOME_ATTESTATION_DOMAIN);
+    message.extend_from_slice(policy.as_ref());
+    message.push(tag);
+    message.extend_from_slice(&delay_minutes.to_le_bytes());
+    message.extend_from_slice(&observed_at.to_le_bytes());
+    message
+}
+
+/// Require that the instruction immediately preceding the current one is a native Ed25519
+/// verification of `expected_message` signed by `expected_signer`. The Ed25519 program has
+/// already checked the signature itself; this only confirms what was verified.
+pub fn verify_ed25519_attestation(
+    instructions_sysvar: &AccountInfo,
+    expected_signer: &Pubkey,
+    expected_message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ZyuraError::InvalidOracleAttestation);
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, ZyuraError::InvalidOracleAttestation);
+
+    let data = &ix.data;
+    require!(data.len() >= ED25519_HEADER_LEN + ED25519_OFFSETS_LEN, ZyuraError::InvalidOracleAttestation);
+    require!(data[0] == 1, ZyuraError::InvalidOracleAttestation);
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = ED25519_HEADER_LEN;
+    let signature_ix_index = read_u16(offsets + 2);
+    let public_key_offset = read_u16(offsets + 4) as usize;
+    let public_key_ix_index = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_ix_index = read_u16(offsets + 12);
+
+    // Signature, key and message must all live in the Ed25519 instruction itself
+    require!(
+        signature_ix_index == u16::MAX && public_key_ix_index == u16::MAX && message_ix_index == u16::MAX,
+        ZyuraError::InvalidOracleAttestation
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + ED25519_PUBKEY_LEN)
+        .ok_or(ZyuraError::InvalidOracleAttestation)?;
+    require!(public_key == expected_signer.as_ref(), ZyuraError::InvalidOracleAttestation);
+
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ZyuraError::InvalidOracleAttestation)?;
+    require!(message == expected_message, ZyuraError::InvalidOracleAttestation);
+    Ok(())
+}
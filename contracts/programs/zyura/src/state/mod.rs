@@ -1,59 +1,457 @@
 use anchor_lang::prelude::*;
+use crate::errors::ZyuraError;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
 pub enum PolicyStatus {
     Active,
     PaidOut,
     Expired,
+    Cancelled,
+}
+
+/// Delegated roles; the admin (protocol owner) implicitly holds every role
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Pauses and unpauses the protocol
+    Pauser,
+    /// Creates products and changes their terms
+    ProductManager,
+    /// Submits payouts for reported flight outcomes (e.g. a keeper bot)
+    Settler,
+    /// Manages pool capital parameters such as the reserve ratio
+    Treasury,
+}
+
+/// Product term change awaiting its timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ProductChange {
+    Terms {
+        delay_threshold_minutes: u32,
+        premium_rate_bps: u16,
+        claim_window_hours: u32,
+    },
+    CancellationTerms {
+        cancellation_cutoff_hours: u32,
+        cancellation_fee_bps: u16,
+    },
+    DelayBands {
+        #[max_len(4)]
+        delay_bands: Vec<DelayBand>,
+    },
+    OutcomePayouts {
+        flight_cancelled_payout_bps: u16,
+        flight_diverted_payout_bps: u16,
+    },
+    SalesWindow {
+        min_lead_time_minutes: u32,
+        max_booking_horizon_days: u32,
+    },
+    CoverageCap {
+        max_active_coverage: u64,
+    },
+    CoverageRange {
+        min_coverage: u64,
+        max_coverage: u64,
+        coverage_step: u64,
+    },
+    PricingCurve {
+        #[max_len(4)]
+        pricing_curve: Vec<PricingPoint>,
+    },
+}
+
+/// Config parameter change awaiting its timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    ReserveRatio { reserve_ratio_bps: u16 },
+    OracleMaxStaleness { max_staleness_secs: i64 },
+    OracleAuthority { oracle_authority: Pubkey },
+    TimelockDelay { timelock_delay_secs: i64 },
+    ExposureCaps { max_flight_coverage: u64, max_policyholder_flight_coverage: u64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct PendingProductChange {
+    pub change: ProductChange,
+    // Earliest unix timestamp at which the change can be executed
+    pub eta: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PendingConfigChange {
+    pub change: ConfigChange,
+    // Earliest unix timestamp at which the change can be executed
+    pub eta: i64,
+}
+
+/// Flight outcome reported for a policy's flight when claiming
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum FlightOutcome {
+    OnTime,
+    Delayed { minutes: u32 },
+    Cancelled,
+    Diverted,
+}
+
+impl FlightOutcome {
+    /// Whether this outcome, reported later, replaces a provisional `recorded` one: a delay only grows while the
+    /// flight is still delayed, and a delayed flight can still be cancelled or diverted.
+    pub fn supersedes(&self, recorded: &FlightOutcome) -> bool {
+        match (*recorded, *self) {
+            (FlightOutcome::Delayed { minutes: before }, FlightOutcome::Delayed { minutes: after }) => after > before,
+            (FlightOutcome::Delayed { .. }, FlightOutcome::Cancelled | FlightOutcome::Diverted) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Payout tier: delays of at least `min_delay_minutes` pay `payout_bps` of the coverage amount
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct DelayBand {
+    pub min_delay_minutes: u32,
+    pub payout_bps: u16,
+}
+
+/// Pricing curve point: at `utilization_bps` of pool utilization the premium is `premium_multiplier_bps`
+/// of the base premium
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PricingPoint {
+    pub utilization_bps: u16,
+    pub premium_multiplier_bps: u16,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
     pub admin: Pubkey,
+    // Admin nominated by `propose_admin`, awaiting `accept_admin` (Pubkey::default() = none)
+    pub pending_admin: Pubkey,
     pub usdc_mint: Pubkey,
     pub switchboard_program: Pubkey,
-    // Re-introduced to match existing on-chain account layout where admin and vault are the same
+    // Program-owned USDC vault (PDA ["risk_pool_vault"]) whose token authority is this config PDA
     pub risk_pool_vault: Pubkey,
+    // LP share mint (PDA ["lp_mint"]) whose mint authority is this config PDA
+    pub lp_mint: Pubkey,
+    // Sum of coverage_amount across all Active policies
+    pub total_active_coverage: u64,
+    // Share of outstanding coverage the vault must hold, in basis points (10_000 = fully collateralized)
+    pub reserve_ratio_bps: u16,
+    // Caps on coverage sold on one flight, in total and to one policyholder (0 = uncapped)
+    pub max_flight_coverage: u64,
+    pub max_policyholder_flight_coverage: u64,
+    // Maximum age of a Switchboard round or signed attestation accepted for claims
+    pub oracle_max_staleness_secs: i64,
+    // Ed25519 key whose signed delay attestations policyholders can claim with
+    pub oracle_authority: Pubkey,
+    // Role holders (Pubkey::default() = unassigned); see `Role`
+    pub pauser: Pubkey,
+    pub product_manager: Pubkey,
+    pub settler: Pubkey,
+    pub treasury: Pubkey,
+    // Minimum wait between queueing a product/config change and executing it
+    pub timelock_delay_secs: i64,
+    // Id assigned to the next `create_product`; starts at 1, `skip_product_id` steps past taken ids
+    pub next_product_id: u64,
+    // Queued parameter change, applied by `execute_config_change` once its eta passes
+    pub pending_change: Option<PendingConfigChange>,
     pub paused: bool,
     pub bump: u8,
 }
 
+// A `localnet` build only compiles with `ZYURA_LOCALNET=1` set, as `yarn test` does, so enabling the
+// feature by accident in a release build fails instead of shipping a one second timelock
+#[cfg(feature = "localnet")]
+const _: () = assert!(
+    option_env!("ZYURA_LOCALNET").is_some(),
+    "the `localnet` feature is for test builds only; set ZYURA_LOCALNET=1 to build it"
+);
+
+impl Config {
+    pub const DEFAULT_RESERVE_RATIO_BPS: u16 = 10_000;
+    pub const DEFAULT_ORACLE_MAX_STALENESS_SECS: i64 = 600;
+    pub const MAX_TIMELOCK_DELAY_SECS: i64 = 30 * 24 * 3600;
+    // The `localnet` feature shrinks the timelock to seconds so the test suite can execute changes;
+    // never deploy a build with it enabled (guarded by `ZYURA_LOCALNET` above)
+    #[cfg(not(feature = "localnet"))]
+    pub const MIN_TIMELOCK_DELAY_SECS: i64 = 24 * 3600;
+    #[cfg(not(feature = "localnet"))]
+    pub const DEFAULT_TIMELOCK_DELAY_SECS: i64 = 2 * 24 * 3600;
+    #[cfg(feature = "localnet")]
+    pub const MIN_TIMELOCK_DELAY_SECS: i64 = 1;
+    #[cfg(feature = "localnet")]
+    pub const DEFAULT_TIMELOCK_DELAY_SECS: i64 = 1;
+
+    /// Current holder of `role`, or `Pubkey::default()` if unassigned.
+    pub fn role_holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::Pauser => self.pauser,
+            Role::ProductManager => self.product_manager,
+            Role::Settler => self.settler,
+            Role::Treasury => self.treasury,
+        }
+    }
+
+    /// Require `signer` to be the admin or the holder of `role`.
+    pub fn require_role(&self, role: Role, signer: &Pubkey) -> Result<()> {
+        let holder = self.role_holder(role);
+        require!(
+            *signer == self.admin || (holder != Pubkey::default() && *signer == holder),
+            ZyuraError::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Outstanding coverage plus `new_coverage` as a share of `pool_value`, in basis points; saturates when the
+    /// pool is empty.
+    pub fn utilization_bps(&self, pool_value: u64, new_coverage: u64) -> Result<u64> {
+        let liability = self.total_active_coverage
+            .checked_add(new_coverage)
+            .ok_or(ZyuraError::MathOverflow)?;
+        if pool_value == 0 {
+            return Ok(u64::MAX);
+        }
+        let utilization = (liability as u128) * 10_000u128 / pool_value as u128;
+        Ok(u64::try_from(utilization).unwrap_or(u64::MAX))
+    }
+
+    /// USDC the vault must hold to back `liability` of outstanding coverage.
+    pub fn required_reserve(&self, liability: u64) -> Result<u64> {
+        let reserve = (liability as u128)
+            .checked_mul(self.reserve_ratio_bps as u128)
+            .ok_or(ZyuraError::MathOverflow)?
+            / 10_000u128;
+        u64::try_from(reserve).map_err(|_| error!(ZyuraError::MathOverflow))
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Product {
     pub id: u64,
     pub delay_threshold_minutes: u32,
-    pub coverage_amount: u64,
+    // Buyers choose coverage from `min_coverage` to `max_coverage` in multiples of `coverage_step` above the minimum
+    pub min_coverage: u64,
+    pub max_coverage: u64,
+    pub coverage_step: u64,
     pub premium_rate_bps: u16,
     pub claim_window_hours: u32,
+    // Tiered payout schedule in ascending delay order; when empty, a delay of at least
+    // `delay_threshold_minutes` pays the full coverage amount
+    #[max_len(4)]
+    pub delay_bands: Vec<DelayBand>,
+    // Premium multiplier by pool utilization, interpolated linearly between points in ascending utilization
+    // order and flat beyond the first and last; when empty, every policy pays the base premium
+    #[max_len(4)]
+    pub pricing_curve: Vec<PricingPoint>,
+    // Share of coverage paid when the flight is cancelled or diverted, in basis points (0 = not covered)
+    pub flight_cancelled_payout_bps: u16,
+    pub flight_diverted_payout_bps: u16,
+    // Policyholders may cancel up to this many hours before departure
+    pub cancellation_cutoff_hours: u32,
+    // Share of the premium kept by the pool on cancellation, in basis points
+    pub cancellation_fee_bps: u16,
+    // Sales close this many minutes before departure
+    pub min_lead_time_minutes: u32,
+    // Policies can be bought at most this many days ahead of departure
+    pub max_booking_horizon_days: u32,
+    // Cap on `active_coverage` (0 = uncapped)
+    pub max_active_coverage: u64,
+    // Queued term change, applied by `execute_product_change` once its eta passes
+    pub pending_change: Option<PendingProductChange>,
+    // Sum of coverage_amount across this product's Active policies
+    pub active_coverage: u64,
+    // Number of this product's policies still Active; the product can only be closed at zero
+    pub active_policies: u64,
     pub active: bool,
     pub bump: u8,
 }
 
+impl Product {
+    pub const DEFAULT_CANCELLATION_CUTOFF_HOURS: u32 = 24;
+    pub const DEFAULT_CANCELLATION_FEE_BPS: u16 = 1_000;
+    pub const DEFAULT_MIN_LEAD_TIME_MINUTES: u32 = 30;
+    pub const DEFAULT_MAX_BOOKING_HORIZON_DAYS: u32 = 365;
+    pub const MAX_DELAY_BANDS: usize = 4;
+    pub const MAX_PRICING_POINTS: usize = 4;
+
+    /// Require a non-empty coverage range that `coverage_step` divides evenly.
+    pub fn validate_coverage_range(min_coverage: u64, max_coverage: u64, coverage_step: u64) -> Result<()> {
+        require!(
+            min_coverage > 0
+                && coverage_step > 0
+                && min_coverage <= max_coverage
+                && (max_coverage - min_coverage).checked_rem(coverage_step) == Some(0),
+            ZyuraError::InvalidCoverageRange
+        );
+        Ok(())
+    }
+
+    /// Require `coverage_amount` to be one of the amounts this product sells.
+    pub fn check_coverage(&self, coverage_amount: u64) -> Result<()> {
+        require!(
+            (self.min_coverage..=self.max_coverage).contains(&coverage_amount)
+                && (coverage_amount - self.min_coverage).checked_rem(self.coverage_step) == Some(0),
+            ZyuraError::InvalidCoverageAmount
+        );
+        Ok(())
+    }
+
+    /// Premium charged for a policy of `coverage_amount` at `utilization_bps` of pool utilization: the base
+    /// premium `coverage_amount * premium_rate_bps / 10_000`, scaled by the pricing curve's multiplier.
+    pub fn premium(&self, coverage_amount: u64, utilization_bps: u64) -> Result<u64> {
+        let premium = (coverage_amount as u128)
+            .checked_mul(self.premium_rate_bps as u128)
+            .and_then(|p| p.checked_mul(self.premium_multiplier_bps(utilization_bps) as u128))
+            .ok_or(ZyuraError::MathOverflow)?
+            / 100_000_000u128;
+        u64::try_from(premium).map_err(|_| error!(ZyuraError::MathOverflow))
+    }
+
+    /// Pricing curve multiplier at `utilization_bps`, in basis points (10_000 = base premium).
+    fn premium_multiplier_bps(&self, utilization_bps: u64) -> u64 {
+        let (Some(first), Some(last)) = (self.pricing_curve.first(), self.pricing_curve.last()) else {
+            return 10_000;
+        };
+        if utilization_bps <= first.utilization_bps as u64 {
+            return first.premium_multiplier_bps as u64;
+        }
+        for pair in self.pricing_curve.windows(2) {
+            let (lower, upper) = (pair[0], pair[1]);
+            if utilization_bps <= upper.utilization_bps as u64 {
+                let span = (upper.utilization_bps - lower.utilization_bps) as u64;
+                let rise = (upper.premium_multiplier_bps - lower.premium_multiplier_bps) as u64;
+                return lower.premium_multiplier_bps as u64
+                    + rise * (utilization_bps - lower.utilization_bps as u64) / span;
+            }
+        }
+        last.premium_multiplier_bps as u64
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Policy {
     pub id: u64,
     pub policyholder: Pubkey,
     pub product_id: u64,
+    // Canonical flight code, e.g. "AI101"
     #[max_len(20)]
     pub flight_number: String,
+    // `Flight` account this policy is settled against
+    pub flight: Pubkey,
     pub departure_time: i64,
     pub premium_paid: u64,
     pub coverage_amount: u64,
+    // Product terms snapshotted at purchase; later `update_product` calls never change a sold policy
+    pub delay_threshold_minutes: u32,
+    pub claim_window_hours: u32,
+    pub premium_rate_bps: u16,
+    #[max_len(4)]
+    pub delay_bands: Vec<DelayBand>,
+    pub flight_cancelled_payout_bps: u16,
+    pub flight_diverted_payout_bps: u16,
+    pub cancellation_cutoff_hours: u32,
+    pub cancellation_fee_bps: u16,
+    // Mint of the soulbound NFT issued with this policy
+    pub nft_mint: Pubkey,
     pub status: PolicyStatus,
     pub created_at: i64,
     pub paid_at: Option<i64>,
     pub bump: u8,
 }
 
+impl Policy {
+    /// Portion of the coverage amount owed for `outcome`; errors if the outcome pays nothing.
+    pub fn payout_for_outcome(&self, outcome: &FlightOutcome) -> Result<u64> {
+        let payout_bps = self.outcome_payout_bps(outcome);
+        if payout_bps == 0 {
+            return Err(match *outcome {
+                FlightOutcome::Delayed { .. } => error!(ZyuraError::DelayThresholdNotMet),
+                _ => error!(ZyuraError::OutcomeNotCovered),
+            });
+        }
+        let payout = (self.coverage_amount as u128)
+            .checked_mul(payout_bps as u128)
+            .ok_or(ZyuraError::MathOverflow)?
+            / 10_000u128;
+        u64::try_from(payout).map_err(|_| error!(ZyuraError::MathOverflow))
+    }
+
+    /// Whether `outcome` pays anything under this policy.
+    pub fn covers(&self, outcome: &FlightOutcome) -> bool {
+        self.outcome_payout_bps(outcome) > 0
+    }
+
+    /// Share of the coverage amount owed for `outcome`, in basis points.
+    fn outcome_payout_bps(&self, outcome: &FlightOutcome) -> u16 {
+        match *outcome {
+            FlightOutcome::OnTime => 0,
+            FlightOutcome::Delayed { minutes } => self.delay_payout_bps(minutes),
+            FlightOutcome::Cancelled => self.flight_cancelled_payout_bps,
+            FlightOutcome::Diverted => self.flight_diverted_payout_bps,
+        }
+    }
+
+    /// Payout share for a delay of `delay_minutes`, from the delay bands or the single threshold.
+    fn delay_payout_bps(&self, delay_minutes: u32) -> u16 {
+        if self.delay_bands.is_empty() {
+            if delay_minutes >= self.delay_threshold_minutes { 10_000 } else { 0 }
+        } else {
+            self.delay_bands
+                .iter()
+                .rev()
+                .find(|band| delay_minutes >= band.min_delay_minutes)
+                .map_or(0, |band| band.payout_bps)
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Flight {
+    // Canonical flight code (see `flight::normalize_flight_number`), zero-padded
+    pub code: [u8; 8],
+    // UTC day of the scheduled departure, in days since the unix epoch
+    pub departure_date: i64,
+    pub scheduled_departure: i64,
+    // Switchboard feed `claim_with_oracle` must read for this flight; default when it has none
+    pub oracle_feed: Pubkey,
+    // Latest outcome paid out on this flight. A delay stays provisional (a larger delay, cancellation or diversion
+    // replaces it) until `settle_flight` makes it final; any other outcome is final once recorded
+    pub outcome: Option<FlightOutcome>,
+    pub outcome_reported_at: i64,
+    // Once set, every later claim must report exactly `outcome`
+    pub outcome_final: bool,
+    // Coverage sold on this flight across all products, net of cancellations
+    pub total_coverage: u64,
+    pub bump: u8,
+}
+
+/// Coverage one policyholder holds on one flight, checked against `Config::max_policyholder_flight_coverage`
+#[account]
+#[derive(InitSpace)]
+pub struct FlightExposure {
+    pub policyholder: Pubkey,
+    pub flight: Pubkey,
+    // Coverage bought on the flight, net of cancellations
+    pub coverage: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Policyholder {
+    pub policyholder: Pubkey,
+    // Number of policies bought so far; the next policy is created under this id
+    pub policy_count: u64,
+    pub bump: u8,
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct LiquidityProvider {
     pub provider: Pubkey,
     pub total_deposited: u64,
     pub total_withdrawn: u64,
-    pub active_deposit: u64,
     pub bump: u8,
 }
\ No newline at end of file
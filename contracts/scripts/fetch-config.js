@@ -1,4 +1,7 @@
+const { BorshAccountsCoder } = require('@coral-xyz/anchor');
 const { Connection, PublicKey } = require('@solana/web3.js');
+// Generated by `anchor build`
+const idl = require('../target/idl/zyura.json');
 
 const PROGRAM_ID = new PublicKey('DWErB1gSbiBBeEaXzy3KEsCbMZCD6sXmrVT9WF9mZgxX');
 const DEFAULT_RPC = 'https://api.devnet.solana.com';
@@ -19,38 +22,21 @@ const DEFAULT_RPC = 'https://api.devnet.solana.com';
       return;
     }
 
-    const data = Buffer.from(accountInfo.data);
     console.log('Lamports:', accountInfo.lamports);
-    console.log('Data length:', data.length);
-
-    if (data.length < 82) {
-      console.warn('Config account data too short to decode expected fields.');
-      return;
-    }
-
-    const admin = new PublicKey(data.slice(8, 40));
-    const usdcMint = new PublicKey(data.slice(40, 72));
-    const switchboardProgram = new PublicKey(data.slice(72, 104));
-
-    let riskPoolVault = null;
-    let pausedOffset = 104;
-
-    if (data.length >= 138) {
-      riskPoolVault = new PublicKey(data.slice(104, 136));
-      pausedOffset = 136;
-    }
-
-    const paused = data[pausedOffset] === 1;
-    const bump = data[pausedOffset + 1];
-
-    console.log('Admin:', admin.toString());
-    console.log('USDC Mint:', usdcMint.toString());
-    console.log('Switchboard Program:', switchboardProgram.toString());
-    if (riskPoolVault) {
-      console.log('Risk Pool Vault:', riskPoolVault.toString());
-    }
-    console.log('Paused:', paused);
-    console.log('Bump:', bump);
+    console.log('Data length:', accountInfo.data.length);
+
+    // Decode with the IDL rather than fixed byte offsets, which break whenever a Config field is added
+    const config = new BorshAccountsCoder(idl).decode('Config', accountInfo.data);
+
+    console.log('Admin:', config.admin.toString());
+    console.log('Pending Admin:', config.pending_admin.equals(PublicKey.default) ? 'none' : config.pending_admin.toString());
+    console.log('USDC Mint:', config.usdc_mint.toString());
+    console.log('Switchboard Program:', config.switchboard_program.toString());
+    console.log('Risk Pool Vault:', config.risk_pool_vault.toString());
+    console.log('LP Mint:', config.lp_mint.toString());
+    console.log('Next Product Id:', config.next_product_id.toString());
+    console.log('Paused:', config.paused);
+    console.log('Bump:', config.bump);
   } catch (error) {
     console.error('Error while fetching config account:', error);
     process.exitCode = 1;
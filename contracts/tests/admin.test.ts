@@ -1,6 +1,8 @@
 import * as anchor from "@coral-xyz/anchor";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
 import { expect } from "chai";
-import { setupTestContext } from "./setup";
+import { setupTestContext, createTestProduct, ensurePoolReserves, executeConfigChange, executeProductChange, flightAccount, purchaseTestPolicy, DELAY_THRESHOLD_MINUTES, MIN_COVERAGE, COVERAGE_AMOUNT, COVERAGE_STEP, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
 
 describe("Admin Functions", () => {
   let ctx: Awaited<ReturnType<typeof setupTestContext>>;
@@ -9,6 +11,33 @@ describe("Admin Functions", () => {
     ctx = await setupTestContext();
   });
 
+  async function chainTime(): Promise<number> {
+    const slot = await ctx.provider.connection.getSlot();
+    return (await ctx.provider.connection.getBlockTime(slot))!;
+  }
+
+  // Role holders pay rent for the accounts they create, so fund them from the provider wallet
+  async function fundedKeypair(): Promise<Keypair> {
+    const keypair = Keypair.generate();
+    const transferTx = SystemProgram.transfer({
+      fromPubkey: ctx.provider.wallet.publicKey,
+      toPubkey: keypair.publicKey,
+      lamports: 0.05 * anchor.web3.LAMPORTS_PER_SOL,
+    });
+    await ctx.provider.sendAndConfirm(new anchor.web3.Transaction().add(transferTx));
+    return keypair;
+  }
+
+  async function expectUnauthorized(send: () => Promise<unknown>, failMessage: string) {
+    try {
+      await send();
+      expect.fail(failMessage);
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg.toLowerCase()).to.include("unauthorized");
+    }
+  }
+
   it("Allows admin to pause the protocol", async () => {
     if (!ctx.isAdminAuthorized) return;
     try {
@@ -96,5 +125,357 @@ describe("Admin Functions", () => {
       expect(errorMsg.toLowerCase()).to.include("unauthorized");
     }
   });
-});
 
+  it("Allows admin to set the reserve ratio", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    const current = config.reserveRatioBps;
+
+    await ctx.program.methods.setReserveRatio(current)
+      .accounts({
+        config: ctx.configAccount,
+        admin: ctx.admin.publicKey,
+      })
+      .signers([ctx.admin])
+      .rpc();
+    await executeConfigChange(ctx);
+    const updated = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(updated.reserveRatioBps).to.equal(current);
+
+    try {
+      await ctx.program.methods.setReserveRatio(0)
+        .accounts({
+          config: ctx.configAccount,
+          admin: ctx.admin.publicKey,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected transaction to fail with a zero reserve ratio");
+    } catch (error: any) {
+      expect(error.message).to.include("Invalid reserve ratio");
+    }
+  });
+
+  it("Lets a granted pauser pause without any other admin powers", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const pauser = ctx.user;
+
+    await ctx.program.methods.grantRole({ pauser: {} }, pauser.publicKey)
+      .accounts({
+        config: ctx.configAccount,
+        admin: ctx.admin.publicKey,
+      })
+      .signers([ctx.admin])
+      .rpc();
+    let config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.pauser.toBase58()).to.equal(pauser.publicKey.toBase58());
+
+    await ctx.program.methods.setPauseStatus(true)
+      .accounts({ config: ctx.configAccount, admin: pauser.publicKey })
+      .signers([pauser])
+      .rpc();
+    await ctx.program.methods.setPauseStatus(false)
+      .accounts({ config: ctx.configAccount, admin: pauser.publicKey })
+      .signers([pauser])
+      .rpc();
+    config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.paused).to.be.false;
+
+    // The pauser role does not extend to treasury parameters
+    try {
+      await ctx.program.methods.setReserveRatio(config.reserveRatioBps)
+        .accounts({ config: ctx.configAccount, admin: pauser.publicKey })
+        .signers([pauser])
+        .rpc();
+      expect.fail("Expected a pauser to be refused the reserve ratio setter");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg.toLowerCase()).to.include("unauthorized");
+    }
+
+    await ctx.program.methods.revokeRole({ pauser: {} })
+      .accounts({
+        config: ctx.configAccount,
+        admin: ctx.admin.publicKey,
+      })
+      .signers([ctx.admin])
+      .rpc();
+    try {
+      await ctx.program.methods.setPauseStatus(true)
+        .accounts({ config: ctx.configAccount, admin: pauser.publicKey })
+        .signers([pauser])
+        .rpc();
+      expect.fail("Expected a revoked pauser to be refused");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg.toLowerCase()).to.include("unauthorized");
+    }
+  });
+
+  it("Rotates the admin key through propose and accept", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const nominee = ctx.user;
+
+    await ctx.program.methods.proposeAdmin(nominee.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await ctx.program.methods.cancelAdminTransfer()
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    let config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.pendingAdmin.toBase58()).to.equal(anchor.web3.PublicKey.default.toBase58());
+
+    // A cancelled nomination cannot be accepted
+    try {
+      await ctx.program.methods.acceptAdmin()
+        .accounts({ config: ctx.configAccount, newAdmin: nominee.publicKey })
+        .signers([nominee])
+        .rpc();
+      expect.fail("Expected accept to fail without a pending transfer");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("No admin transfer is pending");
+    }
+
+    await ctx.program.methods.proposeAdmin(nominee.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await ctx.program.methods.acceptAdmin()
+      .accounts({ config: ctx.configAccount, newAdmin: nominee.publicKey })
+      .signers([nominee])
+      .rpc();
+    config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.admin.toBase58()).to.equal(nominee.publicKey.toBase58());
+
+    // Hand the key back so later suites keep using the original admin
+    await ctx.program.methods.proposeAdmin(ctx.admin.publicKey)
+      .accounts({ config: ctx.configAccount, admin: nominee.publicKey })
+      .signers([nominee])
+      .rpc();
+    await ctx.program.methods.acceptAdmin()
+      .accounts({ config: ctx.configAccount, newAdmin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.admin.toBase58()).to.equal(ctx.admin.publicKey.toBase58());
+  });
+
+  it("Queues config changes behind the timelock and lets admin cancel them", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.timelockDelaySecs.toNumber()).to.be.greaterThan(0);
+
+    // The delay cannot be switched off
+    try {
+      await ctx.program.methods.setTimelockDelay(new anchor.BN(0))
+        .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected a zero timelock delay to be rejected");
+    } catch (error: any) {
+      expect(error.message).to.include("Invalid timelock delay");
+    }
+
+    await ctx.program.methods.setTimelockDelay(new anchor.BN(3600))
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    let queued = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(queued.pendingChange).to.not.be.null;
+    expect(queued.timelockDelaySecs.toString()).to.equal(config.timelockDelaySecs.toString());
+
+    // Only one change may be pending at a time
+    try {
+      await ctx.program.methods.setReserveRatio(config.reserveRatioBps)
+        .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected a second queued change to be rejected");
+    } catch (error: any) {
+      expect(error.message).to.include("A change is already pending");
+    }
+
+    await ctx.program.methods.cancelConfigChange()
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    queued = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(queued.pendingChange).to.be.null;
+    expect(queued.timelockDelaySecs.toString()).to.equal(config.timelockDelaySecs.toString());
+
+    try {
+      await ctx.program.methods.executeConfigChange()
+        .accounts({ config: ctx.configAccount })
+        .rpc();
+      expect.fail("Expected execute to fail with nothing queued");
+    } catch (error: any) {
+      expect(error.message).to.include("No change is pending");
+    }
+  });
+
+  it("Lets a granted settler register flights and pay claims until revoked", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const settler = await fundedKeypair();
+
+    await ctx.program.methods.grantRole({ settler: {} }, settler.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    let config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.settler.toBase58()).to.equal(settler.publicKey.toBase58());
+
+    // A product with no minimum lead time, so the flight can depart moments after the purchases
+    const { productAccount } = await createTestProduct(ctx);
+    await ctx.program.methods.setSalesWindow(0, 365)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    const coverage = product.minCoverage;
+    await ensurePoolReserves(ctx, coverage.muln(2));
+
+    const departure = (await chainTime()) + 15;
+    const flight = flightAccount(ctx, "RS100", departure);
+    await ctx.program.methods.registerFlight("RS100", new anchor.BN(departure), PublicKey.default)
+      .accounts({ config: ctx.configAccount, flight, admin: settler.publicKey, systemProgram: SystemProgram.programId })
+      .signers([settler])
+      .rpc();
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const maxPremium = coverage.muln(product.premiumRateBps).divn(10000).muln(2);
+    const first = await purchaseTestPolicy(ctx, productAccount, "RS100", coverage, maxPremium, departure);
+    const second = await purchaseTestPolicy(ctx, productAccount, "RS100", coverage, maxPremium, departure);
+    while ((await chainTime()) <= departure) {
+      await new Promise(resolve => setTimeout(resolve, 500));
+    }
+
+    // Keeper bot flow: `process_payout` signed by the settler key
+    const outcome = { delayed: { minutes: product.delayThresholdMinutes + 10 } };
+    const payout = (policy: { policyId: anchor.BN; policyAccount: PublicKey }, signer: Keypair) =>
+      ctx.program.methods.processPayout(policy.policyId, outcome)
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policy.policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta.address,
+          admin: signer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    for (const outsider of [ctx.user, ctx.liquidityProvider]) {
+      await expectUnauthorized(() => payout(first, outsider), "Expected a payout signed by a non-settler key to be refused");
+    }
+    await payout(first, settler);
+    expect((await ctx.program.account.policy.fetch(first.policyAccount)).status).to.deep.equal({ paidOut: {} });
+
+    await ctx.program.methods.revokeRole({ settler: {} })
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.settler.toBase58()).to.equal(PublicKey.default.toBase58());
+    await expectUnauthorized(() => payout(second, settler), "Expected a revoked settler to be refused");
+    expect((await ctx.program.account.policy.fetch(second.policyAccount)).status).to.deep.equal({ active: {} });
+
+    // The admin keeps every role, so it settles the remaining policy
+    await payout(second, ctx.admin);
+  });
+
+  it("Lets a granted product manager create and manage products until revoked", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const manager = await fundedKeypair();
+
+    await ctx.program.methods.grantRole({ productManager: {} }, manager.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    let config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.productManager.toBase58()).to.equal(manager.publicKey.toBase58());
+
+    const createProduct = async () => {
+      const { nextProductId } = await ctx.program.account.config.fetch(ctx.configAccount);
+      const [product] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), nextProductId.toArrayLike(Buffer, "le", 8)],
+        ctx.program.programId
+      );
+      await ctx.program.methods.createProduct(DELAY_THRESHOLD_MINUTES, MIN_COVERAGE, COVERAGE_AMOUNT, COVERAGE_STEP, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
+        .accounts({ config: ctx.configAccount, product, admin: manager.publicKey, systemProgram: SystemProgram.programId })
+        .signers([manager])
+        .rpc();
+      return product;
+    };
+    const setActive = (product: PublicKey, active: boolean) =>
+      ctx.program.methods.setProductActive(active)
+        .accounts({ config: ctx.configAccount, product, admin: manager.publicKey })
+        .signers([manager])
+        .rpc();
+
+    const productAccount = await createProduct();
+    let product = await ctx.program.account.product.fetch(productAccount);
+    expect(product.active).to.be.true;
+    await setActive(productAccount, false);
+    product = await ctx.program.account.product.fetch(productAccount);
+    expect(product.active).to.be.false;
+
+    await ctx.program.methods.revokeRole({ productManager: {} })
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.productManager.toBase58()).to.equal(PublicKey.default.toBase58());
+    await expectUnauthorized(() => setActive(productAccount, true), "Expected a revoked product manager to be refused");
+    await expectUnauthorized(createProduct, "Expected a revoked product manager to be refused a new product");
+
+    await ctx.program.methods.closeProduct()
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+  });
+
+  it("Lets a granted treasury set pool parameters until revoked", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const treasury = await fundedKeypair();
+
+    await ctx.program.methods.grantRole({ treasury: {} }, treasury.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.treasury.toBase58()).to.equal(treasury.publicKey.toBase58());
+
+    const setReserveRatio = () =>
+      ctx.program.methods.setReserveRatio(config.reserveRatioBps)
+        .accounts({ config: ctx.configAccount, admin: treasury.publicKey })
+        .signers([treasury])
+        .rpc();
+    await setReserveRatio();
+    await executeConfigChange(ctx);
+    await ctx.program.methods.setExposureCaps(config.maxFlightCoverage, config.maxPolicyholderFlightCoverage)
+      .accounts({ config: ctx.configAccount, admin: treasury.publicKey })
+      .signers([treasury])
+      .rpc();
+    await executeConfigChange(ctx);
+    let updated = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(updated.pendingChange).to.be.null;
+    expect(updated.reserveRatioBps).to.equal(config.reserveRatioBps);
+    expect(updated.maxFlightCoverage.toString()).to.equal(config.maxFlightCoverage.toString());
+
+    await ctx.program.methods.revokeRole({ treasury: {} })
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    updated = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(updated.treasury.toBase58()).to.equal(PublicKey.default.toBase58());
+    await expectUnauthorized(setReserveRatio, "Expected a revoked treasury to be refused");
+    expect((await ctx.program.account.config.fetch(ctx.configAccount)).pendingChange).to.be.null;
+  });
+});
@@ -1,6 +1,7 @@
 import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, getAccount } from "@solana/spl-token";
 import { expect } from "chai";
-import { setupTestContext, SWITCHBOARD_PROGRAM_ID } from "./setup";
+import { setupTestContext, programDataAddress, SWITCHBOARD_PROGRAM_ID } from "./setup";
 
 describe("Initialization", () => {
   let ctx: Awaited<ReturnType<typeof setupTestContext>>;
@@ -25,17 +26,9 @@ describe("Initialization", () => {
         return;
       }
 
-      if (adminMatches || ctx.provider.wallet.publicKey.toString() === existingConfig.admin.toString()) {
-        const closeSig = await ctx.program.methods.closeConfig().accounts({
-            config: ctx.configAccount,
-          admin: ctx.admin.publicKey,
-        }).signers([ctx.admin]).rpc();
-        await ctx.provider.connection.confirmTransaction(closeSig, "confirmed");
-        needsInitialization = true;
-        } else {
-          expect(existingConfig).to.exist;
-          return;
-      }
+      // A config from an earlier run is never replaced; admin-only tests skip themselves on a mismatch
+      expect(existingConfig).to.exist;
+      return;
     } catch (error: any) {
       if (!error.message?.includes("Account does not exist")) throw error;
       needsInitialization = true;
@@ -43,28 +36,72 @@ describe("Initialization", () => {
 
     if (!needsInitialization) return;
 
-    try {
-      await ctx.program.methods.initialize(ctx.admin.publicKey, ctx.usdcMint, SWITCHBOARD_PROGRAM_ID)
+    const initialize = (upgradeAuthority: PublicKey) =>
+      ctx.program.methods.initialize(ctx.admin.publicKey, ctx.usdcMint, SWITCHBOARD_PROGRAM_ID)
         .accounts({
           config: ctx.configAccount,
+          usdcMint: ctx.usdcMint,
+          riskPoolVault: ctx.riskPoolVault,
+          lpMint: ctx.lpMint,
           payer: ctx.admin.publicKey,
+          upgradeAuthority,
+          program: ctx.program.programId,
+          programData: programDataAddress(ctx.program.programId),
+          tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
-        })
-        .signers([ctx.admin])
-        .rpc();
+        });
+
+    // Only the upgrade authority (the deploying wallet) can create the config
+    try {
+      await initialize(ctx.admin.publicKey).signers([ctx.admin]).rpc();
+      expect.fail("Expected initialization by a key other than the upgrade authority to fail");
     } catch (error: any) {
-      if (error.message?.includes("already in use")) {
-        const config = await ctx.program.account.config.fetch(ctx.configAccount);
-        expect(config).to.exist;
-        return;
-      }
-      throw error;
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Unauthorized");
     }
 
+    await initialize(ctx.provider.wallet.publicKey).signers([ctx.admin]).rpc();
+
     const config = await ctx.program.account.config.fetch(ctx.configAccount);
     expect(config.admin.toString()).to.equal(ctx.admin.publicKey.toString());
     expect(config.usdcMint.toString()).to.equal(ctx.usdcMint.toString());
     expect(config.paused).to.be.false;
+    expect(config.riskPoolVault.toString()).to.equal(ctx.riskPoolVault.toString());
+    expect(config.lpMint.toString()).to.equal(ctx.lpMint.toString());
+
+    const vault = await getAccount(ctx.provider.connection, ctx.riskPoolVault);
+    expect(vault.owner.toString()).to.equal(ctx.configAccount.toString());
+    expect(vault.mint.toString()).to.equal(ctx.usdcMint.toString());
   });
-});
 
+  it("Rejects re-initializing an existing config", async () => {
+    const before = await ctx.program.account.config.fetch(ctx.configAccount);
+
+    try {
+      await ctx.program.methods.initialize(ctx.user.publicKey, ctx.usdcMint, ctx.user.publicKey)
+        .accounts({
+          config: ctx.configAccount,
+          usdcMint: ctx.usdcMint,
+          riskPoolVault: ctx.riskPoolVault,
+          lpMint: ctx.lpMint,
+          payer: ctx.user.publicKey,
+          upgradeAuthority: ctx.user.publicKey,
+          program: ctx.program.programId,
+          programData: programDataAddress(ctx.program.programId),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([ctx.user])
+        .rpc();
+      expect.fail("Expected re-initialization to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("already in use");
+    }
+
+    const after = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(after.admin.toString()).to.equal(before.admin.toString());
+    expect(after.switchboardProgram.toString()).to.equal(before.switchboardProgram.toString());
+    expect(after.paused).to.equal(before.paused);
+  });
+});
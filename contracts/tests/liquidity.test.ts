@@ -1,16 +1,35 @@
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, mintTo, getAccount, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, mintTo, transfer, getAccount, getMint, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { expect } from "chai";
 import { setupTestContext } from "./setup";
 
 describe("Liquidity Management", () => {
   let ctx: Awaited<ReturnType<typeof setupTestContext>>;
-  
+
   before(async () => {
     ctx = await setupTestContext();
   });
 
+  async function poolState() {
+    const vault = await getAccount(ctx.provider.connection, ctx.riskPoolVault);
+    const lpMint = await getMint(ctx.provider.connection, ctx.lpMint);
+    return { poolValue: vault.amount, shareSupply: lpMint.supply };
+  }
+
+  // Mirrors the program's virtual shares/assets offset on the share price
+  const VIRTUAL_OFFSET = BigInt(1_000_000);
+  const sharesForDeposit = (amount: bigint, shareSupply: bigint, poolValue: bigint) =>
+    (amount * (shareSupply + VIRTUAL_OFFSET)) / (poolValue + VIRTUAL_OFFSET);
+  const usdcForShares = (shares: bigint, shareSupply: bigint, poolValue: bigint) => {
+    const owed = (shares * (poolValue + VIRTUAL_OFFSET)) / (shareSupply + VIRTUAL_OFFSET);
+    return owed < poolValue ? owed : poolValue;
+  };
+
+  async function lpShareBalance(lpShareAta: PublicKey): Promise<bigint> {
+    return getAccount(ctx.provider.connection, lpShareAta).then(a => a.amount).catch(() => BigInt(0));
+  }
+
   it("Allows liquidity provider to deposit USDC", async () => {
     const [lpAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
@@ -24,6 +43,10 @@ describe("Liquidity Management", () => {
       ctx.liquidityProvider.publicKey
     );
     await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, lpUsdcAta.address, ctx.usdcMintAuthority, 10000 * 1e6);
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
+
+    const { poolValue, shareSupply } = await poolState();
+    const sharesBefore = await lpShareBalance(lpShareAta);
 
     const depositAmount = new anchor.BN(1000 * 1e6);
     await ctx.program.methods.depositLiquidity(depositAmount)
@@ -31,57 +54,71 @@ describe("Liquidity Management", () => {
         config: ctx.configAccount,
         liquidityProvider: lpAccount,
         riskPoolVault: ctx.riskPoolVault,
+        lpMint: ctx.lpMint,
         userUsdcAccount: lpUsdcAta.address,
+        userLpTokenAccount: lpShareAta,
         user: ctx.liquidityProvider.publicKey,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
       .signers([ctx.liquidityProvider])
       .rpc();
 
+    // Shares are priced against pool NAV (an empty pool mints 1:1)
+    const expectedShares = sharesForDeposit(BigInt(depositAmount.toString()), shareSupply, poolValue);
+    const sharesAfter = await lpShareBalance(lpShareAta);
+    expect((sharesAfter - sharesBefore).toString()).to.equal(expectedShares.toString());
+
     const lp = await ctx.program.account.liquidityProvider.fetch(lpAccount);
-    expect(lp.totalDeposited.toString()).to.equal(depositAmount.toString());
-    expect(lp.activeDeposit.toString()).to.equal(depositAmount.toString());
+    expect(lp.totalDeposited.gte(depositAmount)).to.be.true;
   });
 
-  it("Allows liquidity provider to withdraw liquidity", async () => {
-    if (!ctx.isAdminAuthorized) return;
+  it("Allows liquidity provider to withdraw liquidity without admin co-signature", async () => {
     const [lpAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
       ctx.program.programId
     );
 
     const lpAta2 = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
     const withdrawShares = new anchor.BN(500 * 1e6);
 
+    const { poolValue, shareSupply } = await poolState();
+    const sharesBefore = await lpShareBalance(lpShareAta);
+    const usdcBefore = (await getAccount(ctx.provider.connection, lpAta2.address)).amount;
+
     await ctx.program.methods.withdrawLiquidity(withdrawShares)
       .accounts({
         config: ctx.configAccount,
         liquidityProvider: lpAccount,
         riskPoolVault: ctx.riskPoolVault,
+        lpMint: ctx.lpMint,
         userUsdcAccount: lpAta2.address,
+        userLpTokenAccount: lpShareAta,
         user: ctx.liquidityProvider.publicKey,
-        admin: ctx.admin.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
       })
-      .signers([ctx.admin])
+      .signers([ctx.liquidityProvider])
       .rpc();
 
-    const lp = await ctx.program.account.liquidityProvider.fetch(lpAccount);
-    expect(lp.activeDeposit.toString()).to.equal("500000000");
+    const expectedUsdc = usdcForShares(BigInt(withdrawShares.toString()), shareSupply, poolValue);
+    const sharesAfter = await lpShareBalance(lpShareAta);
+    const usdcAfter = (await getAccount(ctx.provider.connection, lpAta2.address)).amount;
+    expect((sharesBefore - sharesAfter).toString()).to.equal(withdrawShares.toString());
+    expect((usdcAfter - usdcBefore).toString()).to.equal(expectedUsdc.toString());
   });
 
-  it("Prevents withdrawing more than active deposit", async () => {
-    if (!ctx.isAdminAuthorized) return;
-    
+  it("Prevents withdrawing more than held LP shares", async () => {
     const [lpAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
       ctx.program.programId
     );
 
-    const lp = await ctx.program.account.liquidityProvider.fetch(lpAccount);
-    const activeDeposit = lp.activeDeposit.toNumber();
-    const excessiveWithdraw = new anchor.BN(activeDeposit + 1000 * 1e6);
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
+    const heldShares = await lpShareBalance(lpShareAta);
+    const excessiveWithdraw = new anchor.BN((heldShares + BigInt(1000 * 1e6)).toString());
 
     try {
       await ctx.program.methods.withdrawLiquidity(excessiveWithdraw)
@@ -89,16 +126,18 @@ describe("Liquidity Management", () => {
           config: ctx.configAccount,
           liquidityProvider: lpAccount,
           riskPoolVault: ctx.riskPoolVault,
+          lpMint: ctx.lpMint,
           userUsdcAccount: await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey).then(a => a.address),
+          userLpTokenAccount: lpShareAta,
           user: ctx.liquidityProvider.publicKey,
-          admin: ctx.admin.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
-        .signers([ctx.admin])
+        .signers([ctx.liquidityProvider])
         .rpc();
-      expect.fail("Expected transaction to fail when withdrawing more than active deposit");
+      expect.fail("Expected transaction to fail when withdrawing more than held LP shares");
     } catch (error: any) {
-      expect(error.message).to.include("Invalid amount");
+      expect(error.message).to.include("Insufficient LP shares");
     }
   });
 
@@ -108,14 +147,8 @@ describe("Liquidity Management", () => {
       ctx.program.programId
     );
 
-    let lpBefore;
-    try {
-      lpBefore = await ctx.program.account.liquidityProvider.fetch(lpAccount);
-    } catch (error) {
-      lpBefore = { totalDeposited: new anchor.BN(0), activeDeposit: new anchor.BN(0) };
-    }
-
     const lpUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
     const lpBalance = await getAccount(ctx.provider.connection, lpUsdcAta.address).catch(() => ({ amount: BigInt(0) }));
     if (lpBalance.amount < BigInt(2000 * 1e6)) {
       await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, lpUsdcAta.address, ctx.usdcMintAuthority, 2000 * 1e6);
@@ -125,7 +158,7 @@ describe("Liquidity Management", () => {
     try {
       lpBeforeFirst = await ctx.program.account.liquidityProvider.fetch(lpAccount);
     } catch (error) {
-      lpBeforeFirst = { totalDeposited: new anchor.BN(0), activeDeposit: new anchor.BN(0) };
+      lpBeforeFirst = { totalDeposited: new anchor.BN(0) };
     }
     const beforeTotal = lpBeforeFirst.totalDeposited.toNumber();
 
@@ -135,8 +168,11 @@ describe("Liquidity Management", () => {
         config: ctx.configAccount,
         liquidityProvider: lpAccount,
         riskPoolVault: ctx.riskPoolVault,
+        lpMint: ctx.lpMint,
         userUsdcAccount: lpUsdcAta.address,
+        userLpTokenAccount: lpShareAta,
         user: ctx.liquidityProvider.publicKey,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -145,12 +181,8 @@ describe("Liquidity Management", () => {
 
     const lpAfterFirst = await ctx.program.account.liquidityProvider.fetch(lpAccount);
     const firstTotal = lpAfterFirst.totalDeposited.toNumber();
-    const firstActiveDeposit = lpAfterFirst.activeDeposit.toNumber();
-    const expectedFirstTotal = beforeTotal + firstDeposit.toNumber();
-    const beforeActiveDeposit = lpBeforeFirst.activeDeposit ? lpBeforeFirst.activeDeposit.toNumber() : 0;
-    const expectedFirstActiveDeposit = beforeActiveDeposit + firstDeposit.toNumber();
-    expect(firstTotal).to.equal(expectedFirstTotal);
-    expect(firstActiveDeposit).to.equal(expectedFirstActiveDeposit);
+    expect(firstTotal).to.equal(beforeTotal + firstDeposit.toNumber());
+    const sharesAfterFirst = await lpShareBalance(lpShareAta);
 
     const secondDeposit = new anchor.BN(300 * 1e6);
     await ctx.program.methods.depositLiquidity(secondDeposit)
@@ -158,8 +190,11 @@ describe("Liquidity Management", () => {
         config: ctx.configAccount,
         liquidityProvider: lpAccount,
         riskPoolVault: ctx.riskPoolVault,
+        lpMint: ctx.lpMint,
         userUsdcAccount: lpUsdcAta.address,
+        userLpTokenAccount: lpShareAta,
         user: ctx.liquidityProvider.publicKey,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -167,10 +202,75 @@ describe("Liquidity Management", () => {
       .rpc();
 
     const lpAfterSecond = await ctx.program.account.liquidityProvider.fetch(lpAccount);
-    const expectedTotal = firstTotal + secondDeposit.toNumber();
-    const expectedActiveDeposit = firstActiveDeposit + secondDeposit.toNumber();
-    expect(lpAfterSecond.totalDeposited.toNumber()).to.equal(expectedTotal);
-    expect(lpAfterSecond.activeDeposit.toNumber()).to.equal(expectedActiveDeposit);
+    expect(lpAfterSecond.totalDeposited.toNumber()).to.equal(firstTotal + secondDeposit.toNumber());
+    const sharesAfterSecond = await lpShareBalance(lpShareAta);
+    expect(sharesAfterSecond > sharesAfterFirst).to.be.true;
   });
-});
 
+  it("Prevents withdrawing into a token account the LP does not own", async () => {
+    const [lpAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
+      ctx.program.programId
+    );
+
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
+    const otherUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+
+    try {
+      await ctx.program.methods.withdrawLiquidity(new anchor.BN(1 * 1e6))
+        .accounts({
+          config: ctx.configAccount,
+          liquidityProvider: lpAccount,
+          riskPoolVault: ctx.riskPoolVault,
+          lpMint: ctx.lpMint,
+          userUsdcAccount: otherUsdcAta.address,
+          userLpTokenAccount: lpShareAta,
+          user: ctx.liquidityProvider.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([ctx.liquidityProvider])
+        .rpc();
+      expect.fail("Expected transaction to fail when withdrawing to another wallet's token account");
+    } catch (error: any) {
+      expect(error.message).to.include("Token account is not owned by the signer");
+    }
+  });
+
+  it("Lets a holder of transferred LP shares withdraw without having deposited", async () => {
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
+    const holderShareAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.lpMint, ctx.user.publicKey);
+    const holderUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    const shares = BigInt(10 * 1e6);
+    await transfer(ctx.provider.connection, ctx.liquidityProvider, lpShareAta, holderShareAta.address, ctx.liquidityProvider, shares);
+
+    const [holderAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_provider"), ctx.user.publicKey.toBuffer()],
+      ctx.program.programId
+    );
+    const { poolValue, shareSupply } = await poolState();
+    const usdcBefore = (await getAccount(ctx.provider.connection, holderUsdcAta.address)).amount;
+
+    await ctx.program.methods.withdrawLiquidity(new anchor.BN(shares.toString()))
+      .accounts({
+        config: ctx.configAccount,
+        liquidityProvider: holderAccount,
+        riskPoolVault: ctx.riskPoolVault,
+        lpMint: ctx.lpMint,
+        userUsdcAccount: holderUsdcAta.address,
+        userLpTokenAccount: holderShareAta.address,
+        user: ctx.user.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([ctx.user])
+      .rpc();
+
+    const expectedUsdc = usdcForShares(shares, shareSupply, poolValue);
+    const usdcAfter = (await getAccount(ctx.provider.connection, holderUsdcAta.address)).amount;
+    expect((usdcAfter - usdcBefore).toString()).to.equal(expectedUsdc.toString());
+    const holder = await ctx.program.account.liquidityProvider.fetch(holderAccount);
+    expect(holder.provider.toBase58()).to.equal(ctx.user.publicKey.toBase58());
+    expect(holder.totalWithdrawn.toString()).to.equal(expectedUsdc.toString());
+  });
+});
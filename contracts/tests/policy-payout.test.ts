@@ -1,5 +1,5 @@
 import * as anchor from "@coral-xyz/anchor";
-import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY, SYSVAR_INSTRUCTIONS_PUBKEY, Ed25519Program } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
   mintTo,
@@ -8,7 +8,7 @@ import {
   getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
 import { expect } from "chai";
-import { setupTestContext, TOKEN_METADATA_PROGRAM_ID, PRODUCT_ID, PREMIUM_AMOUNT, FLIGHT_NUMBER, DEPARTURE_TIME, COVERAGE_AMOUNT, DELAY_THRESHOLD_MINUTES, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
+import { setupTestContext, createTestProduct, nextPolicyAccounts, purchaseTestPolicy, registerTestFlight, flightAccount, flightExposureAccount, ensurePoolReserves, executeProductChange, executeConfigChange, MOCK_SWITCHBOARD_FEED, MOCK_FEED_DELAY_MINUTES, TOKEN_METADATA_PROGRAM_ID, PRODUCT_ID, PREMIUM_AMOUNT, FLIGHT_NUMBER, DEPARTURE_TIME, COVERAGE_AMOUNT, DELAY_THRESHOLD_MINUTES, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
 
 describe("Policy Payout", () => {
   let ctx: Awaited<ReturnType<typeof setupTestContext>>;
@@ -35,15 +35,7 @@ describe("Policy Payout", () => {
             .rpc();
         }
         try {
-          await ctx.program.methods.createProduct(PRODUCT_ID, DELAY_THRESHOLD_MINUTES, COVERAGE_AMOUNT, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
-            .accounts({
-              config: ctx.configAccount,
-              product: productAccount,
-              admin: ctx.admin.publicKey,
-              systemProgram: SystemProgram.programId,
-            })
-            .signers([ctx.admin])
-            .rpc();
+          await createTestProduct(ctx);
           product = await ctx.program.account.product.fetch(productAccount);
         } catch (createError: any) {
           if (createError.message?.includes("already in use")) {
@@ -59,48 +51,77 @@ describe("Policy Payout", () => {
     return { product, productAccount };
   }
 
-  async function ensureVaultLiquidity(product: any) {
-    const vaultBalance = await getAccount(ctx.provider.connection, ctx.riskPoolVault).catch(() => ({ amount: BigInt(0) }));
-    if (vaultBalance.amount < BigInt(product.coverageAmount.toString())) {
-      const lpUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
-      const lpBalance = await getAccount(ctx.provider.connection, lpUsdcAta.address).catch(() => ({ amount: BigInt(0) }));
-      if (lpBalance.amount < BigInt(product.coverageAmount.toString())) {
-        await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, lpUsdcAta.address, ctx.usdcMintAuthority, Number(product.coverageAmount) * 2);
-      }
-      const [lpAccount] = PublicKey.findProgramAddressSync(
-        [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
-        ctx.program.programId
-      );
-      await ctx.program.methods.depositLiquidity(product.coverageAmount)
-        .accounts({
-          config: ctx.configAccount,
-          liquidityProvider: lpAccount,
-          riskPoolVault: ctx.riskPoolVault,
-          userUsdcAccount: lpUsdcAta.address,
-          user: ctx.liquidityProvider.publicKey,
-          tokenProgram: TOKEN_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([ctx.liquidityProvider])
+  async function buyTestPolicy(productAccount: PublicKey, product: any, flightNumber: string, departureTime: number = DEPARTURE_TIME) {
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
+    const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    return purchaseTestPolicy(ctx, productAccount, flightNumber, product.maxCoverage, premiumAmount, departureTime);
+  }
+
+  async function chainTime(): Promise<number> {
+    const slot = await ctx.provider.connection.getSlot();
+    return (await ctx.provider.connection.getBlockTime(slot))!;
+  }
+
+  // Only cancellations can be reported before departure, so tests that pay out sell flights departing moments away
+  async function departingSoon(seconds = 5): Promise<number> {
+    return (await chainTime()) + seconds;
+  }
+
+  async function waitForDeparture(departureTime: number) {
+    while ((await chainTime()) <= departureTime) {
+      await new Promise(resolve => setTimeout(resolve, 500));
+    }
+  }
+
+  // A product with no minimum lead time, so a flight departing moments after purchase can be sold
+  let departingProductId: anchor.BN | null = null;
+  async function ensureDepartingProduct() {
+    if (!departingProductId) {
+      const { productId, productAccount } = await createTestProduct(ctx);
+      await ctx.program.methods.setSalesWindow(0, 365)
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
         .rpc();
+      await executeProductChange(ctx, productAccount);
+      departingProductId = productId;
     }
+    const [productAccount] = PublicKey.findProgramAddressSync([Buffer.from("product"), departingProductId.toArrayLike(Buffer, "le", 8)], ctx.program.programId);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    return { productId: departingProductId, product, productAccount };
+  }
+
+  async function setOracleMaxStaleness(seconds: anchor.BN) {
+    await ctx.program.methods.setOracleMaxStaleness(seconds)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeConfigChange(ctx);
+  }
+
+  const OUTCOME_TAGS = { onTime: 0, delayed: 1, cancelled: 2, diverted: 3 };
+
+  function outcomeAttestation(oracle: Keypair, policyAccount: PublicKey, outcome: any, observedAt: number | bigint) {
+    const variant = Object.keys(outcome)[0] as keyof typeof OUTCOME_TAGS;
+    const tagAndDelay = Buffer.alloc(5);
+    tagAndDelay.writeUInt8(OUTCOME_TAGS[variant]);
+    tagAndDelay.writeUInt32LE(outcome.delayed?.minutes ?? 0, 1);
+    const observed = Buffer.alloc(8);
+    observed.writeBigInt64LE(BigInt(observedAt));
+    const message = Buffer.concat([Buffer.from("ZYURA_OUTCOME_V1"), policyAccount.toBuffer(), tagAndDelay, observed]);
+    return Ed25519Program.createInstructionWithPrivateKey({ privateKey: oracle.secretKey, message });
   }
 
   it("Allows admin to process payout for eligible delay", async () => {
     if (!ctx.isAdminAuthorized) return;
     
-    const productData = await ensureProduct();
-    if (!productData) return;
-    const { product, productAccount } = productData;
-    await ensureVaultLiquidity(product);
+    const { product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage);
 
-    const POLICY_ID_PAYOUT = new anchor.BN(Date.now() + 100);
-    const [policyAccountPayout] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_PAYOUT.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: POLICY_ID_PAYOUT, policyAccount: policyAccountPayout, policyholderAccount: policyholderAccountPayout } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAtaPayout = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -118,11 +139,16 @@ describe("Policy Payout", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(POLICY_ID_PAYOUT, "DD999", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    const departure = await departingSoon();
+    await registerTestFlight(ctx, "DD999", departure);
+    await ctx.program.methods.purchasePolicy("DD999", new anchor.BN(departure), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountPayout,
+        flight: flightAccount(ctx, "DD999", departure),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "DD999", departure), ctx.user.publicKey),
+        policyholder: policyholderAccountPayout,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAtaPayout.address,
         user: ctx.user.publicKey,
@@ -139,16 +165,18 @@ describe("Policy Payout", () => {
       })
       .signers([ctx.user, policyNftMintPayout])
       .rpc();
+    await waitForDeparture(departure);
 
     const delayMinutes = product.delayThresholdMinutes + 10;
     const userBalanceBefore = (await getAccount(ctx.provider.connection, userAtaPayout.address)).amount;
     const vaultBalanceBefore = (await getAccount(ctx.provider.connection, ctx.riskPoolVault)).amount;
 
-    await ctx.program.methods.processPayout(POLICY_ID_PAYOUT, delayMinutes)
+    await ctx.program.methods.processPayout(POLICY_ID_PAYOUT, { delayed: { minutes: delayMinutes } })
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountPayout,
+        flight: flightAccount(ctx, "DD999", departure),
         riskPoolVault: ctx.riskPoolVault,
         policyholderUsdcAccount: userAtaPayout.address,
         admin: ctx.admin.publicKey,
@@ -160,26 +188,25 @@ describe("Policy Payout", () => {
     const policy = await ctx.program.account.policy.fetch(policyAccountPayout);
     expect(policy.status).to.deep.equal({ paidOut: {} });
     expect(policy.paidAt).to.not.be.null;
+    const flight = await ctx.program.account.flight.fetch(flightAccount(ctx, "DD999", departure));
+    expect(flight.outcome).to.deep.equal({ delayed: { minutes: delayMinutes } });
 
     const userBalanceAfter = (await getAccount(ctx.provider.connection, userAtaPayout.address)).amount;
     const vaultBalanceAfter = (await getAccount(ctx.provider.connection, ctx.riskPoolVault)).amount;
-    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(product.coverageAmount));
-    expect(Number(vaultBalanceBefore) - Number(vaultBalanceAfter)).to.equal(Number(product.coverageAmount));
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(product.maxCoverage));
+    expect(Number(vaultBalanceBefore) - Number(vaultBalanceAfter)).to.equal(Number(product.maxCoverage));
   });
 
   it("Prevents payout when delay threshold not met", async () => {
     if (!ctx.isAdminAuthorized) return;
     
-    const POLICY_ID_NO_PAYOUT = new anchor.BN(Date.now() + 200);
-    const [policyAccountNoPayout] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_NO_PAYOUT.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: POLICY_ID_NO_PAYOUT, policyAccount: policyAccountNoPayout, policyholderAccount: policyholderAccountNoPayout } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAtaNoPayout = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -197,11 +224,15 @@ describe("Policy Payout", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(POLICY_ID_NO_PAYOUT, "EE888", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    await registerTestFlight(ctx, "EE888", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("EE888", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountNoPayout,
+        flight: flightAccount(ctx, "EE888", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "EE888", DEPARTURE_TIME), ctx.user.publicKey),
+        policyholder: policyholderAccountNoPayout,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAtaNoPayout.address,
         user: ctx.user.publicKey,
@@ -221,11 +252,12 @@ describe("Policy Payout", () => {
 
     const delayMinutes = product.delayThresholdMinutes - 5;
     try {
-      await ctx.program.methods.processPayout(POLICY_ID_NO_PAYOUT, delayMinutes)
+      await ctx.program.methods.processPayout(POLICY_ID_NO_PAYOUT, { delayed: { minutes: delayMinutes } })
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccountNoPayout,
+          flight: flightAccount(ctx, "EE888", DEPARTURE_TIME),
           riskPoolVault: ctx.riskPoolVault,
           policyholderUsdcAccount: userAtaNoPayout.address,
           admin: ctx.admin.publicKey,
@@ -242,18 +274,12 @@ describe("Policy Payout", () => {
   it("Prevents payout when policy is not active", async () => {
     if (!ctx.isAdminAuthorized) return;
     
-    const productData = await ensureProduct();
-    if (!productData) return;
-    const { product, productAccount } = productData;
-    await ensureVaultLiquidity(product);
+    const { product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage);
 
-    const POLICY_ID_INACTIVE = new anchor.BN(Date.now() + 300);
-    const [policyAccountInactive] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_INACTIVE.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: POLICY_ID_INACTIVE, policyAccount: policyAccountInactive, policyholderAccount: policyholderAccountInactive } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAtaInactive = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -271,11 +297,16 @@ describe("Policy Payout", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(POLICY_ID_INACTIVE, "FF777", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    const departure = await departingSoon();
+    await registerTestFlight(ctx, "FF777", departure);
+    await ctx.program.methods.purchasePolicy("FF777", new anchor.BN(departure), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountInactive,
+        flight: flightAccount(ctx, "FF777", departure),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "FF777", departure), ctx.user.publicKey),
+        policyholder: policyholderAccountInactive,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAtaInactive.address,
         user: ctx.user.publicKey,
@@ -292,15 +323,17 @@ describe("Policy Payout", () => {
       })
       .signers([ctx.user, policyNftMintInactive])
       .rpc();
+    await waitForDeparture(departure);
 
     const delayMinutes = product.delayThresholdMinutes + 10;
     
     try {
-      await ctx.program.methods.processPayout(POLICY_ID_INACTIVE, delayMinutes)
+      await ctx.program.methods.processPayout(POLICY_ID_INACTIVE, { delayed: { minutes: delayMinutes } })
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccountInactive,
+          flight: flightAccount(ctx, "FF777", departure),
           riskPoolVault: ctx.riskPoolVault,
           policyholderUsdcAccount: userAtaInactive.address,
           admin: ctx.admin.publicKey,
@@ -312,11 +345,12 @@ describe("Policy Payout", () => {
       const policyAfterPayout = await ctx.program.account.policy.fetch(policyAccountInactive);
       if (policyAfterPayout.status.paidOut !== undefined) {
         try {
-          await ctx.program.methods.processPayout(POLICY_ID_INACTIVE, delayMinutes)
+          await ctx.program.methods.processPayout(POLICY_ID_INACTIVE, { delayed: { minutes: delayMinutes } })
             .accounts({
               config: ctx.configAccount,
               product: productAccount,
               policy: policyAccountInactive,
+              flight: flightAccount(ctx, "FF777", departure),
               riskPoolVault: ctx.riskPoolVault,
               policyholderUsdcAccount: userAtaInactive.address,
               admin: ctx.admin.publicKey,
@@ -344,16 +378,13 @@ describe("Policy Payout", () => {
   it("Prevents payout when protocol is paused", async () => {
     if (!ctx.isAdminAuthorized) return;
     
-    const POLICY_ID_PAUSED = new anchor.BN(Date.now() + 400);
-    const [policyAccountPaused] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_PAUSED.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: POLICY_ID_PAUSED, policyAccount: policyAccountPaused, policyholderAccount: policyholderAccountPaused } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAtaPaused = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -371,11 +402,15 @@ describe("Policy Payout", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(POLICY_ID_PAUSED, "GG666", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    await registerTestFlight(ctx, "GG666", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("GG666", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountPaused,
+        flight: flightAccount(ctx, "GG666", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "GG666", DEPARTURE_TIME), ctx.user.publicKey),
+        policyholder: policyholderAccountPaused,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAtaPaused.address,
         user: ctx.user.publicKey,
@@ -403,11 +438,12 @@ describe("Policy Payout", () => {
 
     const delayMinutes = DELAY_THRESHOLD_MINUTES + 10;
     try {
-      await ctx.program.methods.processPayout(POLICY_ID_PAUSED, delayMinutes)
+      await ctx.program.methods.processPayout(POLICY_ID_PAUSED, { delayed: { minutes: delayMinutes } })
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccountPaused,
+          flight: flightAccount(ctx, "GG666", DEPARTURE_TIME),
           riskPoolVault: ctx.riskPoolVault,
           policyholderUsdcAccount: userAtaPaused.address,
           admin: ctx.admin.publicKey,
@@ -433,16 +469,13 @@ describe("Policy Payout", () => {
   it("Prevents payout by unauthorized user", async () => {
     if (!ctx.isAdminAuthorized) return;
     
-    const POLICY_ID_UNAUTH = new anchor.BN(Date.now() + 500);
-    const [policyAccountUnauth] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_UNAUTH.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: POLICY_ID_UNAUTH, policyAccount: policyAccountUnauth, policyholderAccount: policyholderAccountUnauth } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAtaUnauth = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -460,11 +493,15 @@ describe("Policy Payout", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(POLICY_ID_UNAUTH, "HH555", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    await registerTestFlight(ctx, "HH555", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("HH555", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccountUnauth,
+        flight: flightAccount(ctx, "HH555", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "HH555", DEPARTURE_TIME), ctx.user.publicKey),
+        policyholder: policyholderAccountUnauth,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAtaUnauth.address,
         user: ctx.user.publicKey,
@@ -484,11 +521,12 @@ describe("Policy Payout", () => {
 
     const delayMinutes = product.delayThresholdMinutes + 10;
     try {
-      await ctx.program.methods.processPayout(POLICY_ID_UNAUTH, delayMinutes)
+      await ctx.program.methods.processPayout(POLICY_ID_UNAUTH, { delayed: { minutes: delayMinutes } })
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccountUnauth,
+          flight: flightAccount(ctx, "HH555", DEPARTURE_TIME),
           riskPoolVault: ctx.riskPoolVault,
           policyholderUsdcAccount: userAtaUnauth.address,
           admin: ctx.user.publicKey,
@@ -502,5 +540,848 @@ describe("Policy Payout", () => {
       expect(errorMsg.toLowerCase()).to.include("unauthorized");
     }
   });
-});
 
+  it("Rejects payout to a token account not owned by the policyholder", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { policyId: POLICY_ID_WRONG_DEST, policyAccount: policyAccountWrongDest, policyholderAccount: policyholderAccountWrongDest } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
+    const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
+
+    const userAtaWrongDest = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAtaWrongDest.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const lpAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
+
+    const policyNftMintWrongDest = Keypair.generate();
+    const userPolicyNftAtaWrongDest = getAssociatedTokenAddressSync(policyNftMintWrongDest.publicKey, ctx.user.publicKey);
+    const [metadataAccountWrongDest] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMintWrongDest.publicKey.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const [masterEditionAccountWrongDest] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMintWrongDest.publicKey.toBuffer(), Buffer.from("edition")],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+    const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+
+    await registerTestFlight(ctx, "II444", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("II444", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
+      .accounts({
+        config: ctx.configAccount,
+        product: productAccount,
+        policy: policyAccountWrongDest,
+        flight: flightAccount(ctx, "II444", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "II444", DEPARTURE_TIME), ctx.user.publicKey),
+        policyholder: policyholderAccountWrongDest,
+        riskPoolVault: ctx.riskPoolVault,
+        userUsdcAccount: userAtaWrongDest.address,
+        user: ctx.user.publicKey,
+        policyNftMint: policyNftMintWrongDest.publicKey,
+        userPolicyNftAta: userPolicyNftAtaWrongDest,
+        metadataAccount: metadataAccountWrongDest,
+        masterEditionAccount: masterEditionAccountWrongDest,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        mintAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([ctx.user, policyNftMintWrongDest])
+      .rpc();
+
+    const delayMinutes = product.delayThresholdMinutes + 10;
+    try {
+      await ctx.program.methods.processPayout(POLICY_ID_WRONG_DEST, { delayed: { minutes: delayMinutes } })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccountWrongDest,
+          flight: flightAccount(ctx, "II444", DEPARTURE_TIME),
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: lpAta.address,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected transaction to fail when paying a non-policyholder account");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Payout token account is not owned by the policyholder");
+    }
+  });
+
+  it("Pays out using the delay reported by the Switchboard feed", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const feedInfo = await ctx.provider.connection.getAccountInfo(MOCK_SWITCHBOARD_FEED);
+    if (!feedInfo) return;
+
+    const { product, productAccount } = await ensureDepartingProduct();
+    if (product.delayThresholdMinutes > MOCK_FEED_DELAY_MINUTES) return;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const departure = await departingSoon();
+    await registerTestFlight(ctx, "SB101", departure, MOCK_SWITCHBOARD_FEED);
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "SB101", departure);
+    await waitForDeparture(departure);
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+
+    // The fixture round has a fixed timestamp, so widen the staleness window for this claim only
+    await setOracleMaxStaleness(new anchor.BN(10_000_000_000));
+    try {
+      await ctx.program.methods.claimWithOracle(policyId)
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          oracleFeed: MOCK_SWITCHBOARD_FEED,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+    } finally {
+      await setOracleMaxStaleness(config.oracleMaxStalenessSecs);
+    }
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ paidOut: {} });
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(policy.coverageAmount));
+  });
+
+  it("Rejects oracle payout when the feed round is stale", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const feedInfo = await ctx.provider.connection.getAccountInfo(MOCK_SWITCHBOARD_FEED);
+    if (!feedInfo) return;
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    await registerTestFlight(ctx, "SB202", DEPARTURE_TIME, MOCK_SWITCHBOARD_FEED);
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "SB202");
+
+    try {
+      await ctx.program.methods.claimWithOracle(policyId)
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          oracleFeed: MOCK_SWITCHBOARD_FEED,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected transaction to fail with a stale oracle feed");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Oracle feed is stale");
+    }
+  });
+
+  it("Rejects oracle payout from a feed not owned by the Switchboard program", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    // Registered with the (non-Switchboard) config account as its feed, so only the owner check can fail
+    await registerTestFlight(ctx, "SB303", DEPARTURE_TIME, ctx.configAccount);
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "SB303");
+
+    try {
+      await ctx.program.methods.claimWithOracle(policyId)
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          oracleFeed: ctx.configAccount,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected transaction to fail with a non-Switchboard feed account");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Invalid oracle feed");
+    }
+  });
+
+  it("Rejects oracle payout from a feed other than the one registered for the flight", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const feedInfo = await ctx.provider.connection.getAccountInfo(MOCK_SWITCHBOARD_FEED);
+    if (!feedInfo) return;
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    // Registered without a feed: a genuine Switchboard feed for some other flight is still refused
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "SB404");
+
+    try {
+      await ctx.program.methods.claimWithOracle(policyId)
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          oracleFeed: MOCK_SWITCHBOARD_FEED,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected transaction to fail with a feed not registered for the flight");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Oracle feed is not the one registered for this flight");
+    }
+  });
+
+  it("Rejects expiring a policy while its claim window is open", async () => {
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const { policyId, policyAccount } = await buyTestPolicy(productAccount, product, "EX101");
+
+    try {
+      await ctx.program.methods.expirePolicy()
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+        })
+        .rpc();
+      expect.fail("Expected transaction to fail while the claim window is open");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Claim window is still open");
+    }
+  });
+
+  it("Expires a policy after its claim window and blocks late payouts", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    // Sell on a product with no lead time or claim window, so the window closes seconds after departure
+    const { productId, productAccount } = await createTestProduct(ctx);
+    await ctx.program.methods.updateProduct(productId, DELAY_THRESHOLD_MINUTES, PREMIUM_RATE_BPS, 0)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    await ctx.program.methods.setSalesWindow(0, 365)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const departure = await departingSoon();
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "EX202", departure);
+    await waitForDeparture(departure);
+
+    try {
+      await ctx.program.methods.processPayout(policyId, { delayed: { minutes: product.delayThresholdMinutes + 10 } })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected payout to fail after the claim window");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Claim window has closed");
+    }
+
+    const configBefore = await ctx.program.account.config.fetch(ctx.configAccount);
+    await ctx.program.methods.expirePolicy()
+      .accounts({
+        config: ctx.configAccount,
+        product: productAccount,
+        policy: policyAccount,
+      })
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ expired: {} });
+    const configAfter = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(configBefore.totalActiveCoverage.sub(configAfter.totalActiveCoverage).toString()).to.equal(policy.coverageAmount.toString());
+  });
+
+  it("Pays out against the terms snapshotted at purchase after the product is updated", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productId, product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const departure = await departingSoon();
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "SN101", departure);
+    await waitForDeparture(departure);
+
+    // Raise the live threshold above the delay we are about to report
+    const delayMinutes = product.delayThresholdMinutes + 10;
+    await ctx.program.methods.updateProduct(productId, delayMinutes + 60, product.premiumRateBps, product.claimWindowHours)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+
+    try {
+      await ctx.program.methods.processPayout(policyId, { delayed: { minutes: delayMinutes } })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policyAccount,
+          flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: userAta,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+    } finally {
+      await ctx.program.methods.updateProduct(productId, product.delayThresholdMinutes, product.premiumRateBps, product.claimWindowHours)
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      await executeProductChange(ctx, productAccount);
+    }
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.delayThresholdMinutes).to.equal(product.delayThresholdMinutes);
+    expect(policy.status).to.deep.equal({ paidOut: {} });
+  });
+
+  it("Lets the policyholder claim with a signed oracle attestation", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const oracleSeed = Buffer.alloc(32);
+    Buffer.from("zyura-test-oracle-seed").copy(oracleSeed);
+    const oracle = Keypair.fromSeed(oracleSeed);
+    await ctx.program.methods.setOracleAuthority(oracle.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeConfigChange(ctx);
+
+    const departure = await departingSoon();
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "AT101", departure);
+    await waitForDeparture(departure);
+    const delayMinutes = product.delayThresholdMinutes + 15;
+    const observedAt = await chainTime();
+
+    const claimAccounts = {
+      config: ctx.configAccount,
+      product: productAccount,
+      policy: policyAccount,
+      flight,
+      riskPoolVault: ctx.riskPoolVault,
+      policyholderUsdcAccount: userAta,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // An attestation for a different delay than the one claimed is rejected
+    try {
+      await ctx.program.methods.claimPayout(policyId, { delayed: { minutes: delayMinutes + 60 } }, new anchor.BN(observedAt))
+        .accounts(claimAccounts)
+        .preInstructions([outcomeAttestation(oracle, policyAccount, { delayed: { minutes: delayMinutes } }, observedAt)])
+        .signers([ctx.user])
+        .rpc();
+      expect.fail("Expected claim to fail with a mismatched attestation");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Missing or invalid oracle outcome attestation");
+    }
+
+    // An observation time far enough in the past to overflow its age is stale, not a panic
+    const i64Min = BigInt("-9223372036854775808");
+    try {
+      await ctx.program.methods.claimPayout(policyId, { delayed: { minutes: delayMinutes } }, new anchor.BN(i64Min.toString()))
+        .accounts(claimAccounts)
+        .preInstructions([outcomeAttestation(oracle, policyAccount, { delayed: { minutes: delayMinutes } }, i64Min)])
+        .signers([ctx.user])
+        .rpc();
+      expect.fail("Expected claim to fail with an overflowing observation time");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Oracle outcome attestation is stale");
+    }
+
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+    await ctx.program.methods.claimPayout(policyId, { delayed: { minutes: delayMinutes } }, new anchor.BN(observedAt))
+      .accounts(claimAccounts)
+      .preInstructions([outcomeAttestation(oracle, policyAccount, { delayed: { minutes: delayMinutes } }, observedAt)])
+      .signers([ctx.user])
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ paidOut: {} });
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(policy.coverageAmount));
+  });
+
+  it("Lets the policyholder cancel before the cutoff for a refund less the fee", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { productAccount } = productData;
+    const cutoffHours = 24;
+    const feeBps = 1000;
+    await ctx.program.methods.setCancellationTerms(cutoffHours, feeBps)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage.muln(2));
+
+    const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+    const cancelAccounts = (policyAccount: PublicKey, flight: PublicKey, userAta: PublicKey, policyNftMint: PublicKey, userPolicyNftAta: PublicKey) => ({
+      config: ctx.configAccount,
+      product: productAccount,
+      policy: policyAccount,
+      flight,
+      flightExposure: flightExposureAccount(ctx, flight, ctx.user.publicKey),
+      riskPoolVault: ctx.riskPoolVault,
+      userUsdcAccount: userAta,
+      policyNftMint,
+      userPolicyNftAta,
+      mintAuthority,
+      user: ctx.user.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    // Departing within the cutoff: cancellation is refused
+    const late = await buyTestPolicy(productAccount, product, "CX100");
+    try {
+      await ctx.program.methods.cancelPolicy()
+        .accounts(cancelAccounts(late.policyAccount, late.flight, late.userAta, late.policyNftMint, late.userPolicyNftAta))
+        .signers([ctx.user])
+        .rpc();
+      expect.fail("Expected cancellation inside the cutoff to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Cancellation cutoff for this policy has passed");
+    }
+
+    const departureTime = Math.floor(Date.now() / 1000) + 3 * 24 * 3600;
+    const { policyId, policyAccount, flight, userAta, policyNftMint, userPolicyNftAta } = await buyTestPolicy(productAccount, product, "CX101", departureTime);
+    const policyBefore = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policyBefore.nftMint.toBase58()).to.equal(policyNftMint.toBase58());
+    const configBefore = await ctx.program.account.config.fetch(ctx.configAccount);
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+
+    await ctx.program.methods.cancelPolicy()
+      .accounts(cancelAccounts(policyAccount, flight, userAta, policyNftMint, userPolicyNftAta))
+      .signers([ctx.user])
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ cancelled: {} });
+    const premium = BigInt(policy.premiumPaid.toString());
+    const expectedRefund = premium - (premium * BigInt(feeBps)) / BigInt(10000);
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect((userBalanceAfter - userBalanceBefore).toString()).to.equal(expectedRefund.toString());
+    expect(await ctx.provider.connection.getAccountInfo(userPolicyNftAta)).to.be.null;
+    const configAfter = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(configBefore.totalActiveCoverage.sub(configAfter.totalActiveCoverage).toString()).to.equal(policy.coverageAmount.toString());
+    // The cancelled coverage no longer counts against the flight caps
+    expect((await ctx.program.account.flight.fetch(flight)).totalCoverage.toNumber()).to.equal(0);
+    const exposure = await ctx.program.account.flightExposure.fetch(flightExposureAccount(ctx, flight, ctx.user.publicKey));
+    expect(exposure.coverage.toNumber()).to.equal(0);
+  });
+
+  it("Pays the portion of coverage for the matching delay band", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+
+    // Bands must ascend in both delay and payout
+    try {
+      await ctx.program.methods.setDelayBands([
+        { minDelayMinutes: 120, payoutBps: 5000 },
+        { minDelayMinutes: 60, payoutBps: 2500 },
+      ])
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected unordered delay bands to be rejected");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Delay bands must ascend");
+    }
+
+    await ctx.program.methods.setDelayBands([
+      { minDelayMinutes: 60, payoutBps: 2500 },
+      { minDelayMinutes: 120, payoutBps: 5000 },
+      { minDelayMinutes: 240, payoutBps: 10000 },
+    ])
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    await ctx.program.methods.setSalesWindow(0, 365)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const departure = await departingSoon();
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "BD100", departure);
+    await waitForDeparture(departure);
+    const payoutAccounts = {
+      config: ctx.configAccount,
+      product: productAccount,
+      policy: policyAccount,
+      flight,
+      riskPoolVault: ctx.riskPoolVault,
+      policyholderUsdcAccount: userAta,
+      admin: ctx.admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Below the first band nothing is owed, even though it clears the legacy threshold
+    try {
+      await ctx.program.methods.processPayout(policyId, { delayed: { minutes: 59 } })
+        .accounts(payoutAccounts)
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected payout below the first band to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Delay threshold not met");
+    }
+
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+    await ctx.program.methods.processPayout(policyId, { delayed: { minutes: 150 } })
+      .accounts(payoutAccounts)
+      .signers([ctx.admin])
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ paidOut: {} });
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(policy.coverageAmount) / 2);
+  });
+
+  it("Pays flight cancellations at the product's configured share and refuses uncovered outcomes", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    await ctx.program.methods.setOutcomePayouts(10000, 0)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const oracleSeed = Buffer.alloc(32);
+    Buffer.from("zyura-test-oracle-seed").copy(oracleSeed);
+    const oracle = Keypair.fromSeed(oracleSeed);
+    await ctx.program.methods.setOracleAuthority(oracle.publicKey)
+      .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeConfigChange(ctx);
+
+    const { policyId, policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "CN100");
+    const observedAt = Math.floor(Date.now() / 1000) - 5;
+    const claimAccounts = {
+      config: ctx.configAccount,
+      product: productAccount,
+      policy: policyAccount,
+      flight,
+      riskPoolVault: ctx.riskPoolVault,
+      policyholderUsdcAccount: userAta,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Diversions pay nothing on this product
+    try {
+      await ctx.program.methods.claimPayout(policyId, { diverted: {} }, new anchor.BN(observedAt))
+        .accounts(claimAccounts)
+        .preInstructions([outcomeAttestation(oracle, policyAccount, { diverted: {} }, observedAt)])
+        .signers([ctx.user])
+        .rpc();
+      expect.fail("Expected a diversion claim to fail on a product that does not cover it");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Flight outcome is not covered by this policy");
+    }
+
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+    await ctx.program.methods.claimPayout(policyId, { cancelled: {} }, new anchor.BN(observedAt))
+      .accounts(claimAccounts)
+      .preInstructions([outcomeAttestation(oracle, policyAccount, { cancelled: {} }, observedAt)])
+      .signers([ctx.user])
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ paidOut: {} });
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(Number(policy.coverageAmount));
+  });
+
+  it("Settles every policy on a flight in one transaction", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage.muln(2));
+
+    const departure = await departingSoon();
+    const first = await buyTestPolicy(productAccount, product, "ST100", departure);
+    const second = await buyTestPolicy(productAccount, product, "ST100", departure);
+    await waitForDeparture(departure);
+    expect(second.flight.toString()).to.equal(first.flight.toString());
+
+    const settlementPairs = [first, second].flatMap(({ policyAccount, userAta }) => [
+      { pubkey: policyAccount, isWritable: true, isSigner: false },
+      { pubkey: userAta, isWritable: true, isSigner: false },
+    ]);
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, first.userAta)).amount;
+    const configBefore = await ctx.program.account.config.fetch(ctx.configAccount);
+
+    const delayMinutes = product.delayThresholdMinutes + 30;
+    await ctx.program.methods.settleFlight({ delayed: { minutes: delayMinutes } })
+      .accounts({
+        config: ctx.configAccount,
+        product: productAccount,
+        flight: first.flight,
+        riskPoolVault: ctx.riskPoolVault,
+        admin: ctx.admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(settlementPairs)
+      .signers([ctx.admin])
+      .rpc();
+
+    for (const { policyAccount } of [first, second]) {
+      const policy = await ctx.program.account.policy.fetch(policyAccount);
+      expect(policy.status).to.deep.equal({ paidOut: {} });
+    }
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, first.userAta)).amount;
+    expect(Number(userBalanceAfter) - Number(userBalanceBefore)).to.equal(2 * Number(product.maxCoverage));
+    const configAfter = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(configBefore.totalActiveCoverage.sub(configAfter.totalActiveCoverage).toString())
+      .to.equal(product.maxCoverage.muln(2).toString());
+    const flight = await ctx.program.account.flight.fetch(first.flight);
+    expect(flight.outcome).to.deep.equal({ delayed: { minutes: delayMinutes } });
+
+    // Resubmitting the batch skips the settled policies, but a different outcome is refused
+    try {
+      await ctx.program.methods.settleFlight({ onTime: {} })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          flight: first.flight,
+          riskPoolVault: ctx.riskPoolVault,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(settlementPairs)
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected settlement with a different outcome to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Outcome differs from the outcome already recorded for this flight");
+    }
+  });
+
+  it("Expires policies on a flight whose outcome they do not cover", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { product, productAccount } = await ensureDepartingProduct();
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    // Far enough out to attempt a settlement before the flight departs
+    const departure = await departingSoon(15);
+    const { policyAccount, flight, userAta } = await buyTestPolicy(productAccount, product, "ST200", departure);
+    const userBalanceBefore = (await getAccount(ctx.provider.connection, userAta)).amount;
+    const settleAccounts = {
+      config: ctx.configAccount,
+      product: productAccount,
+      flight,
+      riskPoolVault: ctx.riskPoolVault,
+      admin: ctx.admin.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const settlementPair = [
+      { pubkey: policyAccount, isWritable: true, isSigner: false },
+      { pubkey: userAta, isWritable: true, isSigner: false },
+    ];
+
+    // Whether the flight ran on time is not known until it departs
+    try {
+      await ctx.program.methods.settleFlight({ onTime: {} })
+        .accounts(settleAccounts)
+        .remainingAccounts(settlementPair)
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected settlement before departure to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Only a cancellation can be reported before the flight's scheduled departure");
+    }
+
+    await waitForDeparture(departure);
+    await ctx.program.methods.settleFlight({ onTime: {} })
+      .accounts(settleAccounts)
+      .remainingAccounts(settlementPair)
+      .signers([ctx.admin])
+      .rpc();
+
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ expired: {} });
+    const userBalanceAfter = (await getAccount(ctx.provider.connection, userAta)).amount;
+    expect(userBalanceAfter.toString()).to.equal(userBalanceBefore.toString());
+    // Departure alone settles nothing; the settlement is what makes the outcome final
+    const settledFlight = await ctx.program.account.flight.fetch(flight);
+    expect(settledFlight.outcome).to.deep.equal({ onTime: {} });
+    expect(settledFlight.outcomeFinal).to.be.true;
+  });
+
+  it("Lets a growing delay replace the recorded one until settlement finalizes it", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    await ctx.program.methods.setDelayBands([
+      { minDelayMinutes: 60, payoutBps: 2500 },
+      { minDelayMinutes: 120, payoutBps: 5000 },
+      { minDelayMinutes: 240, payoutBps: 10000 },
+    ])
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    await ctx.program.methods.setSalesWindow(0, 365)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage.muln(3));
+
+    const departure = await departingSoon();
+    const early = await buyTestPolicy(productAccount, product, "DL300", departure);
+    const later = await buyTestPolicy(productAccount, product, "DL300", departure);
+    const settled = await buyTestPolicy(productAccount, product, "DL300", departure);
+    await waitForDeparture(departure);
+    const payout = (policy: typeof early, minutes: number) =>
+      ctx.program.methods.processPayout(policy.policyId, { delayed: { minutes } })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policy: policy.policyAccount,
+          flight: policy.flight,
+          riskPoolVault: ctx.riskPoolVault,
+          policyholderUsdcAccount: policy.userAta,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([ctx.admin])
+        .rpc();
+    const settle = (minutes: number) =>
+      ctx.program.methods.settleFlight({ delayed: { minutes } })
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          flight: settled.flight,
+          riskPoolVault: ctx.riskPoolVault,
+          admin: ctx.admin.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([
+          { pubkey: settled.policyAccount, isWritable: true, isSigner: false },
+          { pubkey: settled.userAta, isWritable: true, isSigner: false },
+        ])
+        .signers([ctx.admin])
+        .rpc();
+    const balance = async () => Number((await getAccount(ctx.provider.connection, early.userAta)).amount);
+    const coverage = Number(product.maxCoverage);
+
+    // The first claim pays the band the delay has reached so far, without locking the flight to it
+    let before = await balance();
+    await payout(early, 90);
+    expect((await balance()) - before).to.equal(coverage / 4);
+    let flight = await ctx.program.account.flight.fetch(early.flight);
+    expect(flight.outcome).to.deep.equal({ delayed: { minutes: 90 } });
+    expect(flight.outcomeFinal).to.be.false;
+
+    // While the flight is delayed the delay only grows, so a shorter one is refused
+    try {
+      await payout(later, 60);
+      expect.fail("Expected a shorter delay than the recorded one to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Outcome differs from the outcome already recorded for this flight");
+    }
+
+    before = await balance();
+    await payout(later, 150);
+    expect((await balance()) - before).to.equal(coverage / 2);
+    flight = await ctx.program.account.flight.fetch(early.flight);
+    expect(flight.outcome).to.deep.equal({ delayed: { minutes: 150 } });
+    expect(flight.outcomeFinal).to.be.false;
+
+    // Settlement finalizes the delay; after that only the same outcome is accepted
+    before = await balance();
+    await settle(150);
+    expect((await balance()) - before).to.equal(coverage / 2);
+    flight = await ctx.program.account.flight.fetch(early.flight);
+    expect(flight.outcomeFinal).to.be.true;
+    try {
+      await settle(300);
+      expect.fail("Expected a different outcome after finalization to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Outcome differs from the outcome already recorded for this flight");
+    }
+  });
+});
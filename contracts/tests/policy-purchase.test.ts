@@ -2,7 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, mintTo, getAccount, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { expect } from "chai";
-import { setupTestContext, TOKEN_METADATA_PROGRAM_ID, PRODUCT_ID, PREMIUM_AMOUNT, FLIGHT_NUMBER, DEPARTURE_TIME, COVERAGE_AMOUNT, DELAY_THRESHOLD_MINUTES, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
+import { setupTestContext, createTestProduct, nextPolicyAccounts, purchaseTestPolicy, registerTestFlight, flightAccount, flightExposureAccount, ensurePoolReserves, executeProductChange, executeConfigChange, TOKEN_METADATA_PROGRAM_ID, PRODUCT_ID, PREMIUM_AMOUNT, FLIGHT_NUMBER, DEPARTURE_TIME, COVERAGE_AMOUNT, DELAY_THRESHOLD_MINUTES, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
 
 describe("Policy Purchase", () => {
   let ctx: Awaited<ReturnType<typeof setupTestContext>>;
@@ -29,15 +29,7 @@ describe("Policy Purchase", () => {
             .rpc();
         }
         try {
-          await ctx.program.methods.createProduct(PRODUCT_ID, DELAY_THRESHOLD_MINUTES, COVERAGE_AMOUNT, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
-            .accounts({
-              config: ctx.configAccount,
-              product: productAccount,
-              admin: ctx.admin.publicKey,
-              systemProgram: SystemProgram.programId,
-            })
-            .signers([ctx.admin])
-            .rpc();
+          await createTestProduct(ctx);
           product = await ctx.program.account.product.fetch(productAccount);
         } catch (createError: any) {
           if (createError.message?.includes("already in use")) {
@@ -57,12 +49,9 @@ describe("Policy Purchase", () => {
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
 
-    const uniquePolicyId = new anchor.BN(Date.now());
-    const [policyAccount] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), uniquePolicyId.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyId: uniquePolicyId, policyAccount, policyholderAccount } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     try {
       await ctx.program.account.policy.fetch(policyAccount);
@@ -70,7 +59,7 @@ describe("Policy Purchase", () => {
     } catch (error: any) {
       if (!error.message?.includes("Account does not exist") && !error.message?.includes("already exists")) throw error;
     }
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -91,12 +80,16 @@ describe("Policy Purchase", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
+    await registerTestFlight(ctx, FLIGHT_NUMBER, DEPARTURE_TIME);
     try {
-      await ctx.program.methods.purchasePolicy(uniquePolicyId, FLIGHT_NUMBER, new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+      await ctx.program.methods.purchasePolicy(FLIGHT_NUMBER, new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccount,
+          flight: flightAccount(ctx, FLIGHT_NUMBER, DEPARTURE_TIME),
+          flightExposure: flightExposureAccount(ctx, flightAccount(ctx, FLIGHT_NUMBER, DEPARTURE_TIME), ctx.user.publicKey),
+          policyholder: policyholderAccount,
           riskPoolVault: ctx.riskPoolVault,
           userUsdcAccount: userAta.address,
           user: ctx.user.publicKey,
@@ -124,10 +117,18 @@ describe("Policy Purchase", () => {
 
     const policy = await ctx.program.account.policy.fetch(policyAccount);
     expect(policy.id.toString()).to.equal(uniquePolicyId.toString());
+    const holder = await ctx.program.account.policyholder.fetch(policyholderAccount);
+    expect(holder.policyCount.toString()).to.equal(uniquePolicyId.addn(1).toString());
     expect(policy.policyholder.toString()).to.equal(ctx.user.publicKey.toString());
     expect(policy.flightNumber).to.equal(FLIGHT_NUMBER);
     expect(policy.premiumPaid.toString()).to.equal(premiumAmount.toString());
     expect(policy.status).to.deep.equal({ active: {} });
+    expect(policy.delayThresholdMinutes).to.equal(product.delayThresholdMinutes);
+    expect(policy.claimWindowHours).to.equal(product.claimWindowHours);
+    expect(policy.premiumRateBps).to.equal(product.premiumRateBps);
+
+    const configAfter = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(configAfter.totalActiveCoverage.gte(policy.coverageAmount)).to.be.true;
 
     const nftAtaAcc = await getAccount(ctx.provider.connection, userPolicyNftAta);
     expect(Number(nftAtaAcc.amount)).to.equal(1);
@@ -147,11 +148,7 @@ describe("Policy Purchase", () => {
         .rpc();
     }
 
-    const POLICY_ID_2 = new anchor.BN(Date.now());
-    const [policyAccount2] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_2.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyAccount: policyAccount2, policyholderAccount: policyholderAccount2 } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     try {
       await ctx.program.account.policy.fetch(policyAccount2);
@@ -161,7 +158,7 @@ describe("Policy Purchase", () => {
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAta2 = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -179,12 +176,16 @@ describe("Policy Purchase", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
+    await registerTestFlight(ctx, "BB456", DEPARTURE_TIME);
     try {
-      await ctx.program.methods.purchasePolicy(POLICY_ID_2, "BB456", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+      await ctx.program.methods.purchasePolicy("BB456", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccount2,
+          flight: flightAccount(ctx, "BB456", DEPARTURE_TIME),
+          flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "BB456", DEPARTURE_TIME), ctx.user.publicKey),
+          policyholder: policyholderAccount2,
           riskPoolVault: ctx.riskPoolVault,
           userUsdcAccount: userAta2.address,
           user: ctx.user.publicKey,
@@ -221,21 +222,17 @@ describe("Policy Purchase", () => {
     }
   });
 
-  it("Handles insufficient premium amount", async () => {
+  it("Rejects purchase when the premium exceeds max_premium", async () => {
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
 
-    const POLICY_ID_3 = new anchor.BN(Date.now() + 1);
-    const [policyAccount3] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), POLICY_ID_3.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyAccount: policyAccount3, policyholderAccount: policyholderAccount3 } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const userAta3 = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
     await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta3.address, ctx.usdcMintAuthority, 1000 * 1e6);
 
-    const insufficientPremium = new anchor.BN(1 * 1e6);
+    const lowMaxPremium = new anchor.BN(1 * 1e6);
     const policyNftMint3 = Keypair.generate();
     const userPolicyNftAta3 = getAssociatedTokenAddressSync(policyNftMint3.publicKey, ctx.user.publicKey);
     const [metadataAccount3] = PublicKey.findProgramAddressSync(
@@ -248,12 +245,16 @@ describe("Policy Purchase", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
+    await registerTestFlight(ctx, "CC789", DEPARTURE_TIME);
     try {
-      await ctx.program.methods.purchasePolicy(POLICY_ID_3, "CC789", new anchor.BN(DEPARTURE_TIME), insufficientPremium, false, "")
+      await ctx.program.methods.purchasePolicy("CC789", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, lowMaxPremium, false, "")
         .accounts({
           config: ctx.configAccount,
           product: productAccount,
           policy: policyAccount3,
+          flight: flightAccount(ctx, "CC789", DEPARTURE_TIME),
+          flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "CC789", DEPARTURE_TIME), ctx.user.publicKey),
+          policyholder: policyholderAccount3,
           riskPoolVault: ctx.riskPoolVault,
           userUsdcAccount: userAta3.address,
           user: ctx.user.publicKey,
@@ -270,10 +271,10 @@ describe("Policy Purchase", () => {
         })
         .signers([ctx.user, policyNftMint3])
         .rpc();
-      expect.fail("Expected transaction to fail with insufficient premium");
+      expect.fail("Expected transaction to fail when the premium exceeds max_premium");
     } catch (error: any) {
       const errorMsg = error?.message || String(error);
-      expect(errorMsg.toLowerCase()).to.include("insufficient premium");
+      expect(errorMsg).to.include("Premium exceeds the buyer's max_premium");
     }
   });
 
@@ -281,8 +282,9 @@ describe("Policy Purchase", () => {
     const productData = await ensureProduct();
     if (!productData) return;
     const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage.muln(2));
 
-    const requiredPremium = (product.coverageAmount.toNumber() * product.premiumRateBps) / 10000;
+    const requiredPremium = (product.maxCoverage.toNumber() * product.premiumRateBps) / 10000;
     const premiumAmount = new anchor.BN(Math.max(requiredPremium, PREMIUM_AMOUNT.toNumber()));
 
     const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
@@ -291,11 +293,7 @@ describe("Policy Purchase", () => {
       await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 2000 * 1e6);
     }
 
-    const policyId1 = new anchor.BN(Date.now() + 1000);
-    const [policyAccount1] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), policyId1.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyAccount: policyAccount1, policyholderAccount: policyholderAccount1 } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const policyNftMint1 = Keypair.generate();
     const userPolicyNftAta1 = getAssociatedTokenAddressSync(policyNftMint1.publicKey, ctx.user.publicKey);
@@ -309,11 +307,15 @@ describe("Policy Purchase", () => {
     );
     const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
 
-    await ctx.program.methods.purchasePolicy(policyId1, "FLIGHT001", new anchor.BN(DEPARTURE_TIME), premiumAmount, false, "")
+    await registerTestFlight(ctx, "ZY001", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("ZY001", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccount1,
+        flight: flightAccount(ctx, "ZY001", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "ZY001", DEPARTURE_TIME), ctx.user.publicKey),
+        policyholder: policyholderAccount1,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAta.address,
         user: ctx.user.publicKey,
@@ -332,13 +334,9 @@ describe("Policy Purchase", () => {
       .rpc();
 
     const policy1 = await ctx.program.account.policy.fetch(policyAccount1);
-    expect(policy1.flightNumber).to.equal("FLIGHT001");
+    expect(policy1.flightNumber).to.equal("ZY1");
 
-    const policyId2 = new anchor.BN(Date.now() + 2000);
-    const [policyAccount2] = PublicKey.findProgramAddressSync(
-      [Buffer.from("policy"), policyId2.toArrayLike(Buffer, "le", 8)],
-      ctx.program.programId
-    );
+    const { policyAccount: policyAccount2, policyholderAccount: policyholderAccount2 } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
 
     const policyNftMint2 = Keypair.generate();
     const userPolicyNftAta2 = getAssociatedTokenAddressSync(policyNftMint2.publicKey, ctx.user.publicKey);
@@ -351,11 +349,15 @@ describe("Policy Purchase", () => {
       TOKEN_METADATA_PROGRAM_ID
     );
 
-    await ctx.program.methods.purchasePolicy(policyId2, "FLIGHT002", new anchor.BN(DEPARTURE_TIME + 7200), premiumAmount, false, "")
+    await registerTestFlight(ctx, "ZY002", DEPARTURE_TIME + 7200);
+    await ctx.program.methods.purchasePolicy("ZY002", new anchor.BN(DEPARTURE_TIME + 7200), product.maxCoverage, premiumAmount, false, "")
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
         policy: policyAccount2,
+        flight: flightAccount(ctx, "ZY002", DEPARTURE_TIME + 7200),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "ZY002", DEPARTURE_TIME + 7200), ctx.user.publicKey),
+        policyholder: policyholderAccount2,
         riskPoolVault: ctx.riskPoolVault,
         userUsdcAccount: userAta.address,
         user: ctx.user.publicKey,
@@ -374,8 +376,444 @@ describe("Policy Purchase", () => {
       .rpc();
 
     const policy2 = await ctx.program.account.policy.fetch(policyAccount2);
-    expect(policy2.flightNumber).to.equal("FLIGHT002");
+    expect(policy2.flightNumber).to.equal("ZY2");
     expect(policy2.policyholder.toString()).to.equal(ctx.user.publicKey.toString());
   });
-});
 
+  it("Prevents LP withdrawals that would leave active policies underfunded", async () => {
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    if (config.totalActiveCoverage.isZero()) return;
+
+    const [lpAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
+      ctx.program.programId
+    );
+    const lpUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
+    const lpShareAta = getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey);
+    const heldShares = (await getAccount(ctx.provider.connection, lpShareAta)).amount;
+
+    try {
+      await ctx.program.methods.withdrawLiquidity(new anchor.BN(heldShares.toString()))
+        .accounts({
+          config: ctx.configAccount,
+          liquidityProvider: lpAccount,
+          riskPoolVault: ctx.riskPoolVault,
+          lpMint: ctx.lpMint,
+          userUsdcAccount: lpUsdcAta.address,
+          userLpTokenAccount: lpShareAta,
+          user: ctx.liquidityProvider.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([ctx.liquidityProvider])
+        .rpc();
+      expect.fail("Expected withdrawal to fail while policies are outstanding");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Withdrawal would leave the pool below required reserves");
+    }
+  });
+
+  it("Charges exactly the product premium when max_premium is higher", async () => {
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const { policyAccount, policyholderAccount } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
+    const premium = product.maxCoverage.muln(product.premiumRateBps).divn(10000);
+    const maxPremium = premium.muln(2);
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const balanceBefore = (await getAccount(ctx.provider.connection, userAta.address)).amount;
+
+    const policyNftMint = Keypair.generate();
+    const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+    await registerTestFlight(ctx, "OP100", DEPARTURE_TIME);
+    await ctx.program.methods.purchasePolicy("OP100", new anchor.BN(DEPARTURE_TIME), product.maxCoverage, maxPremium, false, "")
+      .accounts({
+        config: ctx.configAccount,
+        product: productAccount,
+        policyholder: policyholderAccount,
+        policy: policyAccount,
+        flight: flightAccount(ctx, "OP100", DEPARTURE_TIME),
+        flightExposure: flightExposureAccount(ctx, flightAccount(ctx, "OP100", DEPARTURE_TIME), ctx.user.publicKey),
+        riskPoolVault: ctx.riskPoolVault,
+        userUsdcAccount: userAta.address,
+        user: ctx.user.publicKey,
+        policyNftMint: policyNftMint.publicKey,
+        userPolicyNftAta: getAssociatedTokenAddressSync(policyNftMint.publicKey, ctx.user.publicKey),
+        metadataAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer()],
+          TOKEN_METADATA_PROGRAM_ID
+        )[0],
+        masterEditionAccount: PublicKey.findProgramAddressSync(
+          [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer(), Buffer.from("edition")],
+          TOKEN_METADATA_PROGRAM_ID
+        )[0],
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        mintAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([ctx.user, policyNftMint])
+      .rpc();
+
+    const balanceAfter = (await getAccount(ctx.provider.connection, userAta.address)).amount;
+    expect((balanceBefore - balanceAfter).toString()).to.equal(premium.toString());
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.premiumPaid.toString()).to.equal(premium.toString());
+  });
+
+  it("Rejects departures inside the minimum lead time or beyond the booking horizon", async () => {
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+    const maxPremium = product.maxCoverage.muln(product.premiumRateBps).divn(10000);
+    const now = Math.floor(Date.now() / 1000);
+
+    const cases = [
+      { departure: now - 3600, error: "Departure is too soon" },
+      { departure: now + product.minLeadTimeMinutes * 60 - 300, error: "Departure is too soon" },
+      { departure: now + (product.maxBookingHorizonDays + 1) * 86400, error: "Departure is beyond the product's booking horizon" },
+    ];
+    for (const [i, { departure, error: expectedError }] of cases.entries()) {
+      const { policyAccount, policyholderAccount } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
+      const policyNftMint = Keypair.generate();
+      // One flight per case, so two departures on the same day do not share a schedule
+      const flightNumber = `SW10${i}`;
+      await registerTestFlight(ctx, flightNumber, departure);
+      try {
+        await ctx.program.methods.purchasePolicy(flightNumber, new anchor.BN(departure), product.maxCoverage, maxPremium, false, "")
+          .accounts({
+            config: ctx.configAccount,
+            product: productAccount,
+            policyholder: policyholderAccount,
+            policy: policyAccount,
+            flight: flightAccount(ctx, flightNumber, departure),
+            flightExposure: flightExposureAccount(ctx, flightAccount(ctx, flightNumber, departure), ctx.user.publicKey),
+            riskPoolVault: ctx.riskPoolVault,
+            userUsdcAccount: userAta.address,
+            user: ctx.user.publicKey,
+            policyNftMint: policyNftMint.publicKey,
+            userPolicyNftAta: getAssociatedTokenAddressSync(policyNftMint.publicKey, ctx.user.publicKey),
+            metadataAccount: PublicKey.findProgramAddressSync(
+              [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer()],
+              TOKEN_METADATA_PROGRAM_ID
+            )[0],
+            masterEditionAccount: PublicKey.findProgramAddressSync(
+              [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer(), Buffer.from("edition")],
+              TOKEN_METADATA_PROGRAM_ID
+            )[0],
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            mintAuthority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([ctx.user, policyNftMint])
+          .rpc();
+        expect.fail(`Expected purchase departing at ${departure} to fail`);
+      } catch (error: any) {
+        const errorMsg = error?.message || String(error);
+        expect(errorMsg).to.include(expectedError);
+      }
+    }
+  });
+
+  it("Only sells flights the settler registered, under their normalized number and schedule", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const productData = await ensureProduct();
+    if (!productData) return;
+    const { product, productAccount } = productData;
+    await ensurePoolReserves(ctx, product.maxCoverage.muln(2));
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+    const maxPremium = product.maxCoverage.muln(product.premiumRateBps).divn(10000);
+
+    async function buy(flightNumber: string, departure: number, flight: PublicKey) {
+      const { policyAccount, policyholderAccount } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
+      const policyNftMint = Keypair.generate();
+      await ctx.program.methods.purchasePolicy(flightNumber, new anchor.BN(departure), product.maxCoverage, maxPremium, false, "")
+        .accounts({
+          config: ctx.configAccount,
+          product: productAccount,
+          policyholder: policyholderAccount,
+          policy: policyAccount,
+          flight,
+          flightExposure: flightExposureAccount(ctx, flight, ctx.user.publicKey),
+          riskPoolVault: ctx.riskPoolVault,
+          userUsdcAccount: userAta.address,
+          user: ctx.user.publicKey,
+          policyNftMint: policyNftMint.publicKey,
+          userPolicyNftAta: getAssociatedTokenAddressSync(policyNftMint.publicKey, ctx.user.publicKey),
+          metadataAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer()],
+            TOKEN_METADATA_PROGRAM_ID
+          )[0],
+          masterEditionAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer(), Buffer.from("edition")],
+            TOKEN_METADATA_PROGRAM_ID
+          )[0],
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          mintAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([ctx.user, policyNftMint])
+        .rpc();
+      return policyAccount;
+    }
+
+    // Buyers cannot insure a flight nobody registered
+    try {
+      await buy("ZY43", DEPARTURE_TIME, flightAccount(ctx, "ZY43", DEPARTURE_TIME));
+      expect.fail("Expected purchase on an unregistered flight to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("AccountNotInitialized");
+    }
+
+    // Only the settler (or admin) registers schedules
+    const registerFlight = (flightNumber: string, departure: number, flight: PublicKey, signer: Keypair) =>
+      ctx.program.methods.registerFlight(flightNumber, new anchor.BN(departure), PublicKey.default)
+        .accounts({ config: ctx.configAccount, flight, admin: signer.publicKey, systemProgram: SystemProgram.programId })
+        .signers([signer])
+        .rpc();
+    const flight = flightAccount(ctx, "ZY42", DEPARTURE_TIME);
+    if (!(await ctx.program.account.flight.fetchNullable(flight))) {
+      try {
+        await registerFlight("ZY42", DEPARTURE_TIME, flight, ctx.user);
+        expect.fail("Expected registration by a non-settler to fail");
+      } catch (error: any) {
+        const errorMsg = error?.message || String(error);
+        expect(errorMsg).to.include("Unauthorized access");
+      }
+    }
+
+    // "zy 0042" and "ZY42" are the same flight
+    await registerTestFlight(ctx, "zy 0042", DEPARTURE_TIME);
+    const flightState = await ctx.program.account.flight.fetch(flight);
+    expect(Buffer.from(flightState.code).toString().replace(/\0+$/, "")).to.equal("ZY42");
+    expect(flightState.scheduledDeparture.toNumber()).to.equal(DEPARTURE_TIME);
+    expect(flightState.outcome).to.be.null;
+    const policyAccount = await buy("ZY42", DEPARTURE_TIME, flight);
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.flightNumber).to.equal("ZY42");
+    expect(policy.flight.toString()).to.equal(flight.toString());
+
+    // A registered schedule cannot be replaced, and buyers must insure it as registered
+    const sameDay = Math.floor((DEPARTURE_TIME + 60) / 86400) === Math.floor(DEPARTURE_TIME / 86400)
+      ? DEPARTURE_TIME + 60
+      : DEPARTURE_TIME - 60;
+    try {
+      await registerFlight("ZY42", sameDay, flight, ctx.admin);
+      expect.fail("Expected re-registering the flight to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("already in use");
+    }
+    try {
+      await buy("ZY42", sameDay, flight);
+      expect.fail("Expected purchase with a different departure time to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Departure time does not match the flight's scheduled departure");
+    }
+
+    // Unparseable numbers derive the all-zero flight code and are rejected at registration
+    const [invalidFlight] = PublicKey.findProgramAddressSync(
+      [Buffer.from("flight"), Buffer.alloc(8), new anchor.BN(Math.floor(DEPARTURE_TIME / 86400)).toArrayLike(Buffer, "le", 8)],
+      ctx.program.programId
+    );
+    try {
+      await registerFlight("FLIGHT001", DEPARTURE_TIME, invalidFlight, ctx.admin);
+      expect.fail("Expected registering an invalid flight number to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Flight number must be a 2-character IATA or 3-letter ICAO carrier code");
+    }
+  });
+
+  it("Enforces the product, flight and per-policyholder coverage caps", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    const coverage = product.maxCoverage;
+    await ensurePoolReserves(ctx, coverage.muln(4));
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const maxPremium = coverage.muln(product.premiumRateBps).divn(10000);
+    const buy = (flightNumber: string) => purchaseTestPolicy(ctx, productAccount, flightNumber, coverage, maxPremium);
+
+    async function expectRejected(flightNumber: string, message: string) {
+      try {
+        await buy(flightNumber);
+        expect.fail(`Expected purchase on ${flightNumber} to exceed a cap`);
+      } catch (error: any) {
+        const errorMsg = error?.message || String(error);
+        expect(errorMsg).to.include(message);
+      }
+    }
+
+    async function setExposureCaps(maxFlightCoverage: anchor.BN, maxPolicyholderFlightCoverage: anchor.BN) {
+      await ctx.program.methods.setExposureCaps(maxFlightCoverage, maxPolicyholderFlightCoverage)
+        .accounts({ config: ctx.configAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      await executeConfigChange(ctx);
+    }
+
+    // Product cap: room for exactly one policy
+    await ctx.program.methods.setCoverageCap(coverage)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    await buy("CP100");
+    await expectRejected("CP101", "Purchase would exceed the product's coverage cap");
+    await ctx.program.methods.setCoverageCap(new anchor.BN(0))
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+
+    try {
+      // One policy per holder per flight, two per flight
+      await setExposureCaps(coverage.muln(2), coverage);
+      await buy("CP200");
+      await expectRejected("CP200", "Purchase would exceed the per-policyholder coverage cap for this flight");
+      const exposure = await ctx.program.account.flightExposure.fetch(
+        flightExposureAccount(ctx, flightAccount(ctx, "CP200", DEPARTURE_TIME), ctx.user.publicKey)
+      );
+      expect(exposure.coverage.toString()).to.equal(coverage.toString());
+
+      // One policy per flight in total
+      await setExposureCaps(coverage, new anchor.BN(0));
+      await buy("CP300");
+      await expectRejected("CP300", "Purchase would exceed the coverage cap for this flight");
+      const flight = await ctx.program.account.flight.fetch(flightAccount(ctx, "CP300", DEPARTURE_TIME));
+      expect(flight.totalCoverage.toString()).to.equal(coverage.toString());
+    } finally {
+      await setExposureCaps(new anchor.BN(0), new anchor.BN(0));
+    }
+  });
+
+  it("Sells the coverage the buyer chooses within the product's range and step", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    await ensurePoolReserves(ctx, product.maxCoverage);
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const maxPremium = product.maxCoverage.muln(product.premiumRateBps).divn(10000);
+    const buy = async (flightNumber: string, coverage: anchor.BN) =>
+      (await purchaseTestPolicy(ctx, productAccount, flightNumber, coverage, maxPremium)).policyAccount;
+
+    // Two steps above the minimum, charged at the premium rate against the chosen amount
+    const coverage = product.minCoverage.add(product.coverageStep.muln(2));
+    const balanceBefore = (await getAccount(ctx.provider.connection, userAta.address)).amount;
+    const policyAccount = await buy("VC100", coverage);
+    const balanceAfter = (await getAccount(ctx.provider.connection, userAta.address)).amount;
+    const premium = coverage.muln(product.premiumRateBps).divn(10000);
+    expect((balanceBefore - balanceAfter).toString()).to.equal(premium.toString());
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.coverageAmount.toString()).to.equal(coverage.toString());
+    expect(policy.premiumPaid.toString()).to.equal(premium.toString());
+
+    const invalid = [
+      product.minCoverage.sub(product.coverageStep.divn(2)),
+      product.minCoverage.add(product.coverageStep.divn(2)),
+      product.maxCoverage.add(product.coverageStep),
+    ];
+    for (const amount of invalid) {
+      try {
+        await buy("VC101", amount);
+        expect.fail(`Expected coverage of ${amount.toString()} to be rejected`);
+      } catch (error: any) {
+        const errorMsg = error?.message || String(error);
+        expect(errorMsg).to.include("Coverage must be within the product's range");
+      }
+    }
+  });
+
+  it("Prices premiums off pool utilization along the product's pricing curve", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    const coverage = product.maxCoverage;
+    await ensurePoolReserves(ctx, coverage);
+    const basePremium = coverage.muln(product.premiumRateBps).divn(10000);
+
+    const quote = () => ctx.program.methods.quotePremium(coverage)
+      .accounts({ config: ctx.configAccount, product: productAccount, riskPoolVault: ctx.riskPoolVault })
+      .view();
+    const setPricingCurve = async (pricingCurve: { utilizationBps: number; premiumMultiplierBps: number }[]) => {
+      await ctx.program.methods.setPricingCurve(pricingCurve)
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      await executeProductChange(ctx, productAccount);
+    };
+
+    // Without a curve the quote is the flat base premium
+    expect((await quote()).toString()).to.equal(basePremium.toString());
+
+    // Premiums may not fall as utilization rises
+    try {
+      await setPricingCurve([
+        { utilizationBps: 5000, premiumMultiplierBps: 20000 },
+        { utilizationBps: 8000, premiumMultiplierBps: 15000 },
+      ]);
+      expect.fail("Expected a falling pricing curve to be rejected");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Invalid pricing curve");
+    }
+
+    // A flat 2x curve doubles the premium, and the purchase charges exactly the quote
+    await setPricingCurve([{ utilizationBps: 0, premiumMultiplierBps: 20000 }]);
+    const doubled = await quote();
+    expect(doubled.toString()).to.equal(basePremium.muln(2).toString());
+
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const { policyAccount } = await purchaseTestPolicy(ctx, productAccount, "DP100", coverage, doubled);
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.premiumPaid.toString()).to.equal(doubled.toString());
+
+    // 1x at an empty pool rising linearly to 3x at full utilization
+    await setPricingCurve([
+      { utilizationBps: 0, premiumMultiplierBps: 10000 },
+      { utilizationBps: 10000, premiumMultiplierBps: 30000 },
+    ]);
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    const vault = await getAccount(ctx.provider.connection, ctx.riskPoolVault);
+    const liability = BigInt(config.totalActiveCoverage.add(coverage).toString());
+    const utilization = (liability * BigInt(10000)) / vault.amount;
+    const multiplier = utilization >= BigInt(10000)
+      ? BigInt(30000)
+      : BigInt(10000) + (BigInt(20000) * utilization) / BigInt(10000);
+    const expected = (BigInt(coverage.toString()) * BigInt(product.premiumRateBps) * multiplier) / BigInt(100_000_000);
+    expect((await quote()).toString()).to.equal(expected.toString());
+  });
+});
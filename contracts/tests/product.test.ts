@@ -1,7 +1,8 @@
 import * as anchor from "@coral-xyz/anchor";
-import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, mintTo, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
 import { expect } from "chai";
-import { setupTestContext, PRODUCT_ID, DELAY_THRESHOLD_MINUTES, COVERAGE_AMOUNT, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
+import { setupTestContext, createTestProduct, executeProductChange, ensurePoolReserves, purchaseTestPolicy, PRODUCT_ID, DELAY_THRESHOLD_MINUTES, MIN_COVERAGE, COVERAGE_AMOUNT, COVERAGE_STEP, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS } from "./setup";
 
 
 describe("Product Management", () => {
@@ -22,7 +23,7 @@ describe("Product Management", () => {
       if (existingProduct.id.toString() === PRODUCT_ID.toString()) {
         expect(existingProduct.id.toString()).to.equal(PRODUCT_ID.toString());
         expect(existingProduct.delayThresholdMinutes).to.equal(DELAY_THRESHOLD_MINUTES);
-        expect(existingProduct.coverageAmount.toString()).to.equal(COVERAGE_AMOUNT.toString());
+        expect(existingProduct.maxCoverage.toString()).to.equal(COVERAGE_AMOUNT.toString());
         expect(existingProduct.premiumRateBps).to.equal(PREMIUM_RATE_BPS);
         expect(existingProduct.active).to.be.true;
         return;
@@ -43,15 +44,7 @@ describe("Product Management", () => {
     } catch (error) {}
 
     try {
-      await ctx.program.methods.createProduct(PRODUCT_ID, DELAY_THRESHOLD_MINUTES, COVERAGE_AMOUNT, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
-        .accounts({
-          config: ctx.configAccount,
-          product: productAccount,
-          admin: ctx.admin.publicKey,
-          systemProgram: SystemProgram.programId,
-        })
-        .signers([ctx.admin])
-        .rpc();
+      await createTestProduct(ctx);
     } catch (error: any) {
       if (!error.message?.includes("already in use")) throw error;
     }
@@ -60,7 +53,7 @@ describe("Product Management", () => {
     expect(product.id.toString()).to.equal(PRODUCT_ID.toString());
     if (product.delayThresholdMinutes === DELAY_THRESHOLD_MINUTES) {
       expect(product.delayThresholdMinutes).to.equal(DELAY_THRESHOLD_MINUTES);
-      expect(product.coverageAmount.toString()).to.equal(COVERAGE_AMOUNT.toString());
+      expect(product.maxCoverage.toString()).to.equal(COVERAGE_AMOUNT.toString());
     }
     expect(product.premiumRateBps).to.equal(PREMIUM_RATE_BPS);
     expect(product.active).to.be.true;
@@ -73,8 +66,7 @@ describe("Product Management", () => {
       ctx.program.programId
     );
     const newDelayThreshold = 45;
-    const newCoverageAmount = new anchor.BN(2000 * 1e6);
-    await ctx.program.methods.updateProduct(PRODUCT_ID, newDelayThreshold, newCoverageAmount, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
+    await ctx.program.methods.updateProduct(PRODUCT_ID, newDelayThreshold, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
       .accounts({
         config: ctx.configAccount,
         product: productAccount,
@@ -82,9 +74,40 @@ describe("Product Management", () => {
       })
       .signers([ctx.admin])
       .rpc();
+    await executeProductChange(ctx, productAccount);
     const product = await ctx.program.account.product.fetch(productAccount);
     expect(product.delayThresholdMinutes).to.equal(newDelayThreshold);
-    expect(product.coverageAmount.toString()).to.equal(newCoverageAmount.toString());
+  });
+
+  it("Allows admin to change the coverage range buyers choose from", async () => {
+    if (!ctx.isAdminAuthorized) return;
+    const [productAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("product"), PRODUCT_ID.toArrayLike(Buffer, "le", 8)],
+      ctx.program.programId
+    );
+
+    // The step must divide the range evenly
+    try {
+      await ctx.program.methods.setCoverageRange(MIN_COVERAGE, new anchor.BN(2050 * 1e6), COVERAGE_STEP)
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected a range the step does not divide to be rejected");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Coverage range must be non-empty");
+    }
+
+    const newMaxCoverage = new anchor.BN(2000 * 1e6);
+    await ctx.program.methods.setCoverageRange(MIN_COVERAGE, newMaxCoverage, COVERAGE_STEP)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    await executeProductChange(ctx, productAccount);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    expect(product.minCoverage.toString()).to.equal(MIN_COVERAGE.toString());
+    expect(product.maxCoverage.toString()).to.equal(newMaxCoverage.toString());
+    expect(product.coverageStep.toString()).to.equal(COVERAGE_STEP.toString());
   });
 
   it("Prevents creating product when protocol is paused", async () => {
@@ -101,14 +124,14 @@ describe("Product Management", () => {
         .rpc();
     }
 
-    const pausedProductId = new anchor.BN(999);
+    const pausedProductId = (await ctx.program.account.config.fetch(ctx.configAccount)).nextProductId;
     const [pausedProductAccount] = PublicKey.findProgramAddressSync(
       [Buffer.from("product"), pausedProductId.toArrayLike(Buffer, "le", 8)],
       ctx.program.programId
     );
 
     try {
-      await ctx.program.methods.createProduct(pausedProductId, DELAY_THRESHOLD_MINUTES, COVERAGE_AMOUNT, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
+      await ctx.program.methods.createProduct(DELAY_THRESHOLD_MINUTES, MIN_COVERAGE, COVERAGE_AMOUNT, COVERAGE_STEP, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
         .accounts({
           config: ctx.configAccount,
           product: pausedProductAccount,
@@ -131,5 +154,101 @@ describe("Product Management", () => {
         .rpc();
     }
   });
-});
 
+  it("Deactivates, reactivates and closes a product without policies", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productId, productAccount } = await createTestProduct(ctx);
+    const created = await ctx.program.account.product.fetch(productAccount);
+    expect(created.id.toString()).to.equal(productId.toString());
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(config.nextProductId.toString()).to.equal(productId.addn(1).toString());
+
+    await ctx.program.methods.setProductActive(false)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    let product = await ctx.program.account.product.fetch(productAccount);
+    expect(product.active).to.be.false;
+
+    await ctx.program.methods.setProductActive(true)
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    product = await ctx.program.account.product.fetch(productAccount);
+    expect(product.active).to.be.true;
+    expect(product.activePolicies.toNumber()).to.equal(0);
+
+    const adminBalanceBefore = await ctx.provider.connection.getBalance(ctx.admin.publicKey);
+    await ctx.program.methods.closeProduct()
+      .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+      .signers([ctx.admin])
+      .rpc();
+    expect(await ctx.provider.connection.getAccountInfo(productAccount)).to.be.null;
+    const adminBalanceAfter = await ctx.provider.connection.getBalance(ctx.admin.publicKey);
+    expect(adminBalanceAfter).to.be.greaterThan(adminBalanceBefore);
+  });
+
+  it("Refuses to skip a free product id or skip without the product manager role", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const config = await ctx.program.account.config.fetch(ctx.configAccount);
+    const [freeProduct] = PublicKey.findProgramAddressSync(
+      [Buffer.from("product"), config.nextProductId.toArrayLike(Buffer, "le", 8)],
+      ctx.program.programId
+    );
+    try {
+      await ctx.program.methods.skipProductId()
+        .accounts({ config: ctx.configAccount, product: freeProduct, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected skipping a free product id to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Product id is free; only an id held by an existing product can be skipped");
+    }
+
+    const outsider = Keypair.generate();
+    try {
+      await ctx.program.methods.skipProductId()
+        .accounts({ config: ctx.configAccount, product: freeProduct, admin: outsider.publicKey })
+        .signers([outsider])
+        .rpc();
+      expect.fail("Expected skipping without the product manager role to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Unauthorized");
+    }
+
+    const after = await ctx.program.account.config.fetch(ctx.configAccount);
+    expect(after.nextProductId.toString()).to.equal(config.nextProductId.toString());
+  });
+
+  it("Refuses to close a product while a policy on it is active", async () => {
+    if (!ctx.isAdminAuthorized) return;
+
+    const { productAccount } = await createTestProduct(ctx);
+    const product = await ctx.program.account.product.fetch(productAccount);
+    const coverage = product.minCoverage;
+    await ensurePoolReserves(ctx, coverage);
+    const userAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.user, ctx.usdcMint, ctx.user.publicKey);
+    await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, userAta.address, ctx.usdcMintAuthority, 1000 * 1e6);
+    const maxPremium = coverage.muln(product.premiumRateBps).divn(10000).muln(2);
+    const { policyAccount } = await purchaseTestPolicy(ctx, productAccount, "CP100", coverage, maxPremium);
+    expect((await ctx.program.account.product.fetch(productAccount)).activePolicies.toNumber()).to.equal(1);
+
+    try {
+      await ctx.program.methods.closeProduct()
+        .accounts({ config: ctx.configAccount, product: productAccount, admin: ctx.admin.publicKey })
+        .signers([ctx.admin])
+        .rpc();
+      expect.fail("Expected closing a product with an active policy to fail");
+    } catch (error: any) {
+      const errorMsg = error?.message || String(error);
+      expect(errorMsg).to.include("Product still has active policies");
+    }
+    expect(await ctx.provider.connection.getAccountInfo(productAccount)).to.not.be.null;
+    const policy = await ctx.program.account.policy.fetch(policyAccount);
+    expect(policy.status).to.deep.equal({ active: {} });
+  });
+});
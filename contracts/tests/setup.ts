@@ -1,11 +1,20 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Zyura } from "../target/types/zyura";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { createMint, createAccount, getAccount, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync, createCloseAccountInstruction } from "@solana/spl-token";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, mintTo, getAccount, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync, createCloseAccountInstruction } from "@solana/spl-token";
 
 export const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 export const SWITCHBOARD_PROGRAM_ID = new PublicKey("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+// Loaded into the local validator from tests/fixtures/mock-switchboard-feed.json (90 minute delay)
+export const MOCK_SWITCHBOARD_FEED = new PublicKey("7YML4qQPbeSc27ZcxP61ze7tq7cwuNLkuCrwepUdAdHg");
+export const MOCK_FEED_DELAY_MINUTES = 90;
+export const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// ProgramData account of an upgradeable program; `initialize` checks its upgrade authority
+export function programDataAddress(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([programId.toBuffer()], BPF_LOADER_UPGRADEABLE_PROGRAM_ID)[0];
+}
 
 export interface TestContext {
   program: Program<Zyura>;
@@ -17,12 +26,16 @@ export interface TestContext {
   usdcMintAuthority: Keypair;
   configAccount: PublicKey;
   riskPoolVault: PublicKey;
+  lpMint: PublicKey;
   isAdminAuthorized: boolean;
 }
 
 export const PRODUCT_ID = new anchor.BN(1);
 export const DELAY_THRESHOLD_MINUTES = 30;
+// Test products sell 100-1000 USDC of coverage in 100 USDC steps; tests buy the maximum unless stated
+export const MIN_COVERAGE = new anchor.BN(100 * 1e6);
 export const COVERAGE_AMOUNT = new anchor.BN(1000 * 1e6);
+export const COVERAGE_STEP = new anchor.BN(100 * 1e6);
 export const PREMIUM_RATE_BPS = 100;
 export const CLAIM_WINDOW_HOURS = 24;
 export const PREMIUM_AMOUNT = new anchor.BN(10 * 1e6);
@@ -95,8 +108,8 @@ export async function setupTestContext(): Promise<TestContext> {
 
   const usdcMint = await createMint(provider.connection, usdcMintAuthority, usdcMintAuthority.publicKey, null, 6);
   const [configAccount] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
-  const [riskPoolVaultPda] = PublicKey.findProgramAddressSync([Buffer.from("risk_pool_vault")], program.programId);
-  const riskPoolVault = await createAccount(provider.connection, admin, usdcMint, admin.publicKey);
+  const [riskPoolVault] = PublicKey.findProgramAddressSync([Buffer.from("risk_pool_vault")], program.programId);
+  const [lpMint] = PublicKey.findProgramAddressSync([Buffer.from("lp_mint")], program.programId);
 
   let isAdminAuthorized = false;
   try {
@@ -119,11 +132,220 @@ export async function setupTestContext(): Promise<TestContext> {
     usdcMintAuthority,
     configAccount,
     riskPoolVault,
+    lpMint,
     isAdminAuthorized,
   };
   return globalContext;
 }
 
+// Deposits LP liquidity until the vault can back `coverageAmount` of new coverage at the reserve ratio
+export async function ensurePoolReserves(ctx: TestContext, coverageAmount: anchor.BN): Promise<void> {
+  const config = await ctx.program.account.config.fetch(ctx.configAccount);
+  const newLiability = BigInt(config.totalActiveCoverage.add(coverageAmount).toString());
+  const required = (newLiability * BigInt(config.reserveRatioBps)) / BigInt(10000);
+  const vaultBalance = (await getAccount(ctx.provider.connection, ctx.riskPoolVault)).amount;
+  if (vaultBalance >= required) return;
+
+  const shortfall = required - vaultBalance;
+  const lpUsdcAta = await getOrCreateAssociatedTokenAccount(ctx.provider.connection, ctx.liquidityProvider, ctx.usdcMint, ctx.liquidityProvider.publicKey);
+  await mintTo(ctx.provider.connection, ctx.usdcMintAuthority, ctx.usdcMint, lpUsdcAta.address, ctx.usdcMintAuthority, shortfall);
+  const [lpAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("liquidity_provider"), ctx.liquidityProvider.publicKey.toBuffer()],
+    ctx.program.programId
+  );
+  await ctx.program.methods.depositLiquidity(new anchor.BN(shortfall.toString()))
+    .accounts({
+      config: ctx.configAccount,
+      liquidityProvider: lpAccount,
+      riskPoolVault: ctx.riskPoolVault,
+      lpMint: ctx.lpMint,
+      userUsdcAccount: lpUsdcAta.address,
+      userLpTokenAccount: getAssociatedTokenAddressSync(ctx.lpMint, ctx.liquidityProvider.publicKey),
+      user: ctx.liquidityProvider.publicKey,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.liquidityProvider])
+    .rpc();
+}
+
+// Creates a product with the default test terms under the next id from the config counter
+export async function createTestProduct(ctx: TestContext): Promise<{ productId: anchor.BN; productAccount: PublicKey }> {
+  const config = await ctx.program.account.config.fetch(ctx.configAccount);
+  const productId = config.nextProductId;
+  const [productAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("product"), productId.toArrayLike(Buffer, "le", 8)],
+    ctx.program.programId
+  );
+  await ctx.program.methods.createProduct(DELAY_THRESHOLD_MINUTES, MIN_COVERAGE, COVERAGE_AMOUNT, COVERAGE_STEP, PREMIUM_RATE_BPS, CLAIM_WINDOW_HOURS)
+    .accounts({
+      config: ctx.configAccount,
+      product: productAccount,
+      admin: ctx.admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.admin])
+    .rpc();
+  return { productId, productAccount };
+}
+
+// Policy PDA the program will allocate for `owner`'s next purchase, from their policy counter
+export async function nextPolicyAccounts(ctx: TestContext, owner: PublicKey): Promise<{ policyId: anchor.BN; policyAccount: PublicKey; policyholderAccount: PublicKey }> {
+  const [policyholderAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("policyholder"), owner.toBuffer()],
+    ctx.program.programId
+  );
+  const holder = await ctx.program.account.policyholder.fetchNullable(policyholderAccount);
+  const policyId = holder ? holder.policyCount : new anchor.BN(0);
+  const [policyAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("policy"), owner.toBuffer(), policyId.toArrayLike(Buffer, "le", 8)],
+    ctx.program.programId
+  );
+  return { policyId, policyAccount, policyholderAccount };
+}
+
+// Mirrors `flight::normalize_flight_number`: "zy 0042" -> "ZY42"; throws on numbers the program rejects
+export function normalizeFlightNumber(raw: string): string {
+  const match = raw.replace(/ /g, "").toUpperCase().match(/^([A-Z]{3}|[A-Z0-9]{2})(\d{1,4})([A-Z]?)$/);
+  if (!match || /^\d{2}$/.test(match[1]) || Number(match[2]) === 0) {
+    throw new Error(`Invalid flight number: ${raw}`);
+  }
+  return `${match[1]}${Number(match[2])}${match[3]}`;
+}
+
+// Flight PDA keyed on the canonical flight code (zero-padded to 8 bytes) and UTC departure date
+export function flightAccount(ctx: TestContext, flightNumber: string, departureTime: number): PublicKey {
+  const code = Buffer.alloc(8);
+  code.write(normalizeFlightNumber(flightNumber));
+  const departureDate = new anchor.BN(Math.floor(departureTime / 86400));
+  const [flight] = PublicKey.findProgramAddressSync(
+    [Buffer.from("flight"), code, departureDate.toArrayLike(Buffer, "le", 8)],
+    ctx.program.programId
+  );
+  return flight;
+}
+
+export function flightExposureAccount(ctx: TestContext, flight: PublicKey, owner: PublicKey): PublicKey {
+  const [exposure] = PublicKey.findProgramAddressSync(
+    [Buffer.from("flight_exposure"), flight.toBuffer(), owner.toBuffer()],
+    ctx.program.programId
+  );
+  return exposure;
+}
+
+// Registers the flight's schedule and oracle feed with the admin (who holds every role, including settler)
+// unless already registered
+export async function registerTestFlight(
+  ctx: TestContext,
+  flightNumber: string,
+  departureTime: number = DEPARTURE_TIME,
+  oracleFeed: PublicKey = PublicKey.default
+): Promise<PublicKey> {
+  const flight = flightAccount(ctx, flightNumber, departureTime);
+  if (await ctx.program.account.flight.fetchNullable(flight)) return flight;
+  await ctx.program.methods.registerFlight(flightNumber, new anchor.BN(departureTime), oracleFeed)
+    .accounts({
+      config: ctx.configAccount,
+      flight,
+      admin: ctx.admin.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([ctx.admin])
+    .rpc();
+  return flight;
+}
+
+// Buys `coverage` on `flightNumber` for `ctx.user`, registering the flight first if needed and paying from
+// their (already funded) USDC account
+export async function purchaseTestPolicy(
+  ctx: TestContext,
+  productAccount: PublicKey,
+  flightNumber: string,
+  coverage: anchor.BN,
+  maxPremium: anchor.BN,
+  departureTime: number = DEPARTURE_TIME
+): Promise<{ policyId: anchor.BN; policyAccount: PublicKey; flight: PublicKey; userAta: PublicKey; policyNftMint: PublicKey; userPolicyNftAta: PublicKey }> {
+  const { policyId, policyAccount, policyholderAccount } = await nextPolicyAccounts(ctx, ctx.user.publicKey);
+  const flight = await registerTestFlight(ctx, flightNumber, departureTime);
+  const userAta = getAssociatedTokenAddressSync(ctx.usdcMint, ctx.user.publicKey);
+  const policyNftMint = Keypair.generate();
+  const userPolicyNftAta = getAssociatedTokenAddressSync(policyNftMint.publicKey, ctx.user.publicKey);
+  const [metadataAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+  const [masterEditionAccount] = PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), policyNftMint.publicKey.toBuffer(), Buffer.from("edition")],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+  const [mintAuthority] = PublicKey.findProgramAddressSync([Buffer.from("policy_mint_authority")], ctx.program.programId);
+
+  await ctx.program.methods.purchasePolicy(flightNumber, new anchor.BN(departureTime), coverage, maxPremium, false, "")
+    .accounts({
+      config: ctx.configAccount,
+      product: productAccount,
+      policyholder: policyholderAccount,
+      policy: policyAccount,
+      flight,
+      flightExposure: flightExposureAccount(ctx, flight, ctx.user.publicKey),
+      riskPoolVault: ctx.riskPoolVault,
+      userUsdcAccount: userAta,
+      user: ctx.user.publicKey,
+      policyNftMint: policyNftMint.publicKey,
+      userPolicyNftAta,
+      metadataAccount,
+      masterEditionAccount,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      mintAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    })
+    .signers([ctx.user, policyNftMint])
+    .rpc();
+
+  return { policyId, policyAccount, flight, userAta, policyNftMint: policyNftMint.publicKey, userPolicyNftAta };
+}
+
+// Waits for the cluster clock to reach `eta`. Test builds use the `localnet` feature's one second
+// timelock; a deployed build's day-long minimum cannot be waited out, so fail fast instead
+async function waitForEta(ctx: TestContext, eta: anchor.BN): Promise<void> {
+  for (;;) {
+    const slot = await ctx.provider.connection.getSlot();
+    const now = (await ctx.provider.connection.getBlockTime(slot))!;
+    const remaining = eta.toNumber() - now;
+    if (remaining <= 0) return;
+    if (remaining > 60) {
+      throw new Error(`Timelock eta is ${remaining}s away; run the tests with 'yarn test' (a localnet build)`);
+    }
+    await new Promise((resolve) => setTimeout(resolve, 500));
+  }
+}
+
+// Applies a product's queued change once its timelock has elapsed
+export async function executeProductChange(ctx: TestContext, productAccount: PublicKey): Promise<void> {
+  const product = await ctx.program.account.product.fetch(productAccount);
+  if (product.pendingChange) {
+    await waitForEta(ctx, product.pendingChange.eta);
+  }
+  await ctx.program.methods.executeProductChange()
+    .accounts({ config: ctx.configAccount, product: productAccount })
+    .rpc();
+}
+
+// Applies the queued config change once its timelock has elapsed
+export async function executeConfigChange(ctx: TestContext): Promise<void> {
+  const config = await ctx.program.account.config.fetch(ctx.configAccount);
+  if (config.pendingChange) {
+    await waitForEta(ctx, config.pendingChange.eta);
+  }
+  await ctx.program.methods.executeConfigChange()
+    .accounts({ config: ctx.configAccount })
+    .rpc();
+}
+
 export async function cleanupTestAccounts(ctx: TestContext): Promise<void> {
   const isDevnet = ctx.provider.connection.rpcEndpoint.includes('devnet');
   const isLocalnet =
@@ -155,31 +377,11 @@ export async function cleanupTestAccounts(ctx: TestContext): Promise<void> {
       }
     };
 
-    const closeConfig = async () => {
-      try {
-        const configInfo = await ctx.provider.connection.getAccountInfo(ctx.configAccount);
-        if (configInfo) {
-          await ctx.program.methods.closeConfig().accounts({
-            config: ctx.configAccount,
-            admin: ctx.admin.publicKey,
-          }).signers([ctx.admin]).rpc();
-          console.log("✓ Config account closed");
-        }
-      } catch (error: any) {
-        if (!error.message?.includes("Account does not exist")) {
-          console.log(`✗ Could not close config: ${error.message}`);
-        }
-      }
-    };
-
-    await closeConfig();
-
     const userUsdcAta = getAssociatedTokenAddressSync(ctx.usdcMint, ctx.user.publicKey);
     const lpUsdcAta = getAssociatedTokenAddressSync(ctx.usdcMint, ctx.liquidityProvider.publicKey);
     
     cleanupPromises.push(closeTokenAccount(userUsdcAta, ctx.user, "User USDC account"));
     cleanupPromises.push(closeTokenAccount(lpUsdcAta, ctx.liquidityProvider, "LP USDC account"));
-    cleanupPromises.push(closeTokenAccount(ctx.riskPoolVault, ctx.admin, "Risk pool vault"));
 
     await Promise.all(cleanupPromises);
 
@@ -2,7 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Zyura } from "../target/types/zyura";
 import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint, createAccount, mintTo, getAccount, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, createMint, mintTo, getAccount, getOrCreateAssociatedTokenAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { expect } from "chai";
 
 const sleep = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
@@ -32,14 +32,18 @@ describe("zyura", () => {
   let configAccount: PublicKey;
   let productAccount: PublicKey;
   let riskPoolVault: PublicKey;
+  let lpMint: PublicKey;
   const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
   const SWITCHBOARD_PROGRAM_ID = new PublicKey("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+  const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
   // Test data
   const PRODUCT_ID = new anchor.BN(1);
   const POLICY_ID = new anchor.BN(1);
   const DELAY_THRESHOLD_MINUTES = 30;
+  const MIN_COVERAGE = new anchor.BN(100 * 1e6); // 100 USDC
   const COVERAGE_AMOUNT = new anchor.BN(1000 * 1e6); // 1000 USDC
+  const COVERAGE_STEP = new anchor.BN(100 * 1e6); // 100 USDC
   const PREMIUM_RATE_BPS = 100; // 1%
   const CLAIM_WINDOW_HOURS = 24;
   const PREMIUM_AMOUNT = new anchor.BN(10 * 1e6); // 10 USDC
@@ -65,7 +69,14 @@ describe("zyura", () => {
         .initialize(admin.publicKey, usdcMint, SWITCHBOARD_PROGRAM_ID)
         .accounts({
           config: configAccount,
+          usdcMint,
+          riskPoolVault,
+          lpMint,
           payer: admin.publicKey,
+          upgradeAuthority: provider.wallet.publicKey,
+          program: program.programId,
+          programData: PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_PROGRAM_ID)[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
         .signers([admin])
@@ -78,7 +89,7 @@ describe("zyura", () => {
       const product = await program.account.product.fetch(productAccount);
       if (!product.active) {
         await program.methods
-          .updateProduct(PRODUCT_ID, product.delayThresholdMinutes, product.coverageAmount, product.premiumRateBps, product.claimWindowHours)
+          .setProductActive(true)
           .accounts({
             config: configAccount,
             product: productAccount,
@@ -91,9 +102,10 @@ describe("zyura", () => {
       if (!error.message?.includes("Account does not exist")) throw error;
       await program.methods
         .createProduct(
-          PRODUCT_ID,
           DELAY_THRESHOLD_MINUTES,
+          MIN_COVERAGE,
           COVERAGE_AMOUNT,
+          COVERAGE_STEP,
           PREMIUM_RATE_BPS,
           CLAIM_WINDOW_HOURS
         )
@@ -179,13 +191,9 @@ describe("zyura", () => {
       program.programId
     );
 
-    const riskPoolVaultAccount = await createAccount(
-      provider.connection,
-      admin,
-      usdcMint,
-      admin.publicKey
+    [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint")],
+      program.programId
     );
-
-    riskPoolVault = riskPoolVaultAccount;
   });
 });
\ No newline at end of file
@@ -1,5 +1,6 @@
 import * as anchor from '@coral-xyz/anchor'
 import { Keypair, PublicKey, SystemProgram, Connection, clusterApiUrl, TransactionInstruction, Transaction } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token'
 import bs58 from 'bs58'
 import { createRequire } from 'module'
 const require = createRequire(import.meta.url)
@@ -23,11 +24,13 @@ const ADMIN_KEYPAIR_PATH = (() => {
 const PHANTOM_SECRET_KEY = process.env.PHANTOM_SECRET_KEY // base58-encoded 64-byte secret key from Phantom export
 // Your Devnet USDC-like mint
 const USDC_MINT = new PublicKey('4sCh4YUdsFuUFTaMyAx3SVnHvHkY9XNq1LX4L6nnWUtv')
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
 
-// Product params (6dp)
-const PRODUCT_ID = new BN(2)
+// Product params (6dp): buyers choose 50-100 USDC of coverage in 50 USDC steps
 const DELAY_MIN = 30
-const COVERAGE_6DP = new BN(100_000_000) // 100.000000
+const MIN_COVERAGE_6DP = new BN(50_000_000) // 50.000000
+const MAX_COVERAGE_6DP = new BN(100_000_000) // 100.000000
+const COVERAGE_STEP_6DP = new BN(50_000_000) // 50.000000
 const PREMIUM_BPS = 100
 const CLAIM_HOURS = 24
 
@@ -51,10 +54,6 @@ async function main() {
   const coder = new anchor.BorshCoder(idlJson as anchor.Idl)
 
   const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID)
-  const [productPda] = PublicKey.findProgramAddressSync(
-    [Buffer.from('product'), PRODUCT_ID.toArrayLike(Buffer, 'le', 8)],
-    PROGRAM_ID,
-  )
 
   // Initialize
   {
@@ -66,10 +65,21 @@ async function main() {
         admin: admin.publicKey,
         usdc_mint: USDC_MINT,
         switchboard_program: new PublicKey('SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f'),
-      } as any)
+      })
+      const [riskPoolVault] = PublicKey.findProgramAddressSync([Buffer.from('risk_pool_vault')], PROGRAM_ID)
+      const [lpMint] = PublicKey.findProgramAddressSync([Buffer.from('lp_mint')], PROGRAM_ID)
+      const [programData] = PublicKey.findProgramAddressSync([PROGRAM_ID.toBuffer()], BPF_LOADER_UPGRADEABLE_PROGRAM_ID)
+      // Only the program's upgrade authority can initialize, so the admin keypair must be the deploy wallet
       const keys = [
         { pubkey: configPda, isWritable: true, isSigner: false },
+        { pubkey: USDC_MINT, isWritable: false, isSigner: false },
+        { pubkey: riskPoolVault, isWritable: true, isSigner: false },
+        { pubkey: lpMint, isWritable: true, isSigner: false },
         { pubkey: admin.publicKey, isWritable: true, isSigner: true },
+        { pubkey: admin.publicKey, isWritable: false, isSigner: true },
+        { pubkey: PROGRAM_ID, isWritable: false, isSigner: false },
+        { pubkey: programData, isWritable: false, isSigner: false },
+        { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
         { pubkey: SystemProgram.programId, isWritable: false, isSigner: false },
       ]
       const ix = new TransactionInstruction({ programId: PROGRAM_ID, keys, data })
@@ -82,19 +92,28 @@ async function main() {
     }
   }
 
-  // Create product
+  // Create product under the next id allocated by the config counter
   {
+    const configInfo = await connection.getAccountInfo(configPda)
+    if (!configInfo) throw new Error('Config not initialized')
+    const config: any = coder.accounts.decode('Config', configInfo.data)
+    const productId = new BN(config.next_product_id.toString())
+    const [productPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('product'), productId.toArrayLike(Buffer, 'le', 8)],
+      PROGRAM_ID,
+    )
     const exists = await connection.getAccountInfo(productPda)
     if (exists) {
       console.log('Product already exists:', productPda.toBase58())
     } else {
       const data = coder.instruction.encode('create_product', {
-        product_id: PRODUCT_ID, // u64
         delay_threshold_minutes: DELAY_MIN, // u32
-        coverage_amount: COVERAGE_6DP, // u64
+        min_coverage: MIN_COVERAGE_6DP, // u64
+        max_coverage: MAX_COVERAGE_6DP, // u64
+        coverage_step: COVERAGE_STEP_6DP, // u64
         premium_rate_bps: PREMIUM_BPS, // u16
         claim_window_hours: CLAIM_HOURS, // u32
-      } as any)
+      })
       const keys = [
         { pubkey: configPda, isWritable: true, isSigner: false },
         { pubkey: productPda, isWritable: true, isSigner: false },
@@ -107,7 +126,7 @@ async function main() {
       tx.feePayer = admin.publicKey
       tx.recentBlockhash = blockhash
       const sig = await provider.sendAndConfirm(tx, [])
-      console.log('Created product:', sig)
+      console.log(`Created product ${productId.toString()}:`, sig)
     }
   }
 }
@@ -14,7 +14,18 @@ function loadKeypair(fp: string): Keypair {
   return Keypair.fromSecretKey(Uint8Array.from(raw))
 }
 
-async function createProduct(connection: Connection, coder: anchor.BorshCoder, admin: Keypair, p: { id: number; delayMin: number; coverage6dp: number; premiumBps: number; claimHours: number; label: string }) {
+type ProductSpec = {
+  id: number
+  delayMin: number
+  minCoverage6dp: number
+  maxCoverage6dp: number
+  coverageStep6dp: number
+  premiumBps: number
+  claimHours: number
+  label: string
+}
+
+async function createProduct(connection: Connection, coder: anchor.BorshCoder, admin: Keypair, p: ProductSpec) {
   const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID)
   const [productPda] = PublicKey.findProgramAddressSync([Buffer.from('product'), new BN(p.id).toArrayLike(Buffer, 'le', 8)], PROGRAM_ID)
 
@@ -24,14 +35,23 @@ async function createProduct(connection: Connection, coder: anchor.BorshCoder, a
     return
   }
 
+  // The program assigns ids from its counter, so products must be created in id order
+  const configInfo = await connection.getAccountInfo(configPda)
+  if (!configInfo) throw new Error('Config not initialized')
+  const config: any = coder.accounts.decode('Config', configInfo.data)
+  if (Number(config.next_product_id.toString()) !== p.id) {
+    throw new Error(`Next product id is ${config.next_product_id.toString()}, cannot create product ${p.id}`)
+  }
+
   console.log(`Creating product ${p.id}: ${p.label}`)
   const data = coder.instruction.encode('create_product', {
-    product_id: new BN(p.id),
     delay_threshold_minutes: p.delayMin,
-    coverage_amount: new BN(p.coverage6dp),
+    min_coverage: new BN(p.minCoverage6dp),
+    max_coverage: new BN(p.maxCoverage6dp),
+    coverage_step: new BN(p.coverageStep6dp),
     premium_rate_bps: p.premiumBps,
     claim_window_hours: p.claimHours,
-  } as any)
+  })
 
   const keys = [
     { pubkey: configPda, isSigner: false, isWritable: true },
@@ -60,12 +80,12 @@ async function main() {
   const idlJson = JSON.parse(fs.readFileSync(idlPath, 'utf8')) as anchor.Idl
   const coder = new anchor.BorshCoder(idlJson)
 
-  const products = [
-    { id: 1, delayMin: 60, coverage6dp: 100_000_000, premiumBps: 120, claimHours: 24, label: 'Domestic Basic (60m, $100)' },
-    { id: 2, delayMin: 90, coverage6dp: 200_000_000, premiumBps: 150, claimHours: 48, label: 'Domestic Plus (90m, $200)' },
-    { id: 3, delayMin: 120, coverage6dp: 500_000_000, premiumBps: 180, claimHours: 48, label: 'International Basic (120m, $500)' },
-    { id: 4, delayMin: 180, coverage6dp: 1_000_000_000, premiumBps: 220, claimHours: 72, label: 'International Plus (180m, $1000)' },
-    { id: 5, delayMin: 240, coverage6dp: 2_000_000_000, premiumBps: 250, claimHours: 96, label: 'Long-Haul Premium (240m, $2000)' },
+  const products: ProductSpec[] = [
+    { id: 1, delayMin: 60, minCoverage6dp: 50_000_000, maxCoverage6dp: 100_000_000, coverageStep6dp: 50_000_000, premiumBps: 120, claimHours: 24, label: 'Domestic Basic (60m, $50-$100)' },
+    { id: 2, delayMin: 90, minCoverage6dp: 100_000_000, maxCoverage6dp: 200_000_000, coverageStep6dp: 50_000_000, premiumBps: 150, claimHours: 48, label: 'Domestic Plus (90m, $100-$200)' },
+    { id: 3, delayMin: 120, minCoverage6dp: 250_000_000, maxCoverage6dp: 500_000_000, coverageStep6dp: 250_000_000, premiumBps: 180, claimHours: 48, label: 'International Basic (120m, $250-$500)' },
+    { id: 4, delayMin: 180, minCoverage6dp: 500_000_000, maxCoverage6dp: 1_000_000_000, coverageStep6dp: 250_000_000, premiumBps: 220, claimHours: 72, label: 'International Plus (180m, $500-$1000)' },
+    { id: 5, delayMin: 240, minCoverage6dp: 1_000_000_000, maxCoverage6dp: 2_000_000_000, coverageStep6dp: 500_000_000, premiumBps: 250, claimHours: 96, label: 'Long-Haul Premium (240m, $1000-$2000)' },
   ]
 
   for (const p of products) {
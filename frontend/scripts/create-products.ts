@@ -38,16 +38,18 @@ async function main() {
   const products: Array<{
     id: number
     delayMin: number
-    coverage6dp: number
+    minCoverage6dp: number
+    maxCoverage6dp: number
+    coverageStep6dp: number
     premiumBps: number
     claimHours: number
     label: string
   }> = [
-    { id: 1, delayMin: 60, coverage6dp: 100_000_000, premiumBps: 120, claimHours: 24, label: 'Domestic Basic (60m, $100)' },
-    { id: 2, delayMin: 90, coverage6dp: 200_000_000, premiumBps: 150, claimHours: 48, label: 'Domestic Plus (90m, $200)' },
-    { id: 3, delayMin: 120, coverage6dp: 500_000_000, premiumBps: 180, claimHours: 48, label: 'International Basic (120m, $500)' },
-    { id: 4, delayMin: 180, coverage6dp: 1_000_000_000, premiumBps: 220, claimHours: 72, label: 'International Plus (180m, $1000)' },
-    { id: 5, delayMin: 240, coverage6dp: 2_000_000_000, premiumBps: 250, claimHours: 96, label: 'Long-Haul Premium (240m, $2000)' },
+    { id: 1, delayMin: 60, minCoverage6dp: 50_000_000, maxCoverage6dp: 100_000_000, coverageStep6dp: 50_000_000, premiumBps: 120, claimHours: 24, label: 'Domestic Basic (60m, $50-$100)' },
+    { id: 2, delayMin: 90, minCoverage6dp: 100_000_000, maxCoverage6dp: 200_000_000, coverageStep6dp: 50_000_000, premiumBps: 150, claimHours: 48, label: 'Domestic Plus (90m, $100-$200)' },
+    { id: 3, delayMin: 120, minCoverage6dp: 250_000_000, maxCoverage6dp: 500_000_000, coverageStep6dp: 250_000_000, premiumBps: 180, claimHours: 48, label: 'International Basic (120m, $250-$500)' },
+    { id: 4, delayMin: 180, minCoverage6dp: 500_000_000, maxCoverage6dp: 1_000_000_000, coverageStep6dp: 250_000_000, premiumBps: 220, claimHours: 72, label: 'International Plus (180m, $500-$1000)' },
+    { id: 5, delayMin: 240, minCoverage6dp: 1_000_000_000, maxCoverage6dp: 2_000_000_000, coverageStep6dp: 500_000_000, premiumBps: 250, claimHours: 96, label: 'Long-Haul Premium (240m, $1000-$2000)' },
   ]
 
   for (const p of products) {
@@ -62,12 +64,19 @@ async function main() {
       continue
     }
 
+    // The program assigns ids from its counter, so products must be created in id order
+    const config: any = await program.account.config.fetch(configPda)
+    if (config.nextProductId.toNumber() !== p.id) {
+      throw new Error(`Next product id is ${config.nextProductId.toString()}, cannot create product ${p.id}`)
+    }
+
     console.log(`Creating product ${p.id}: ${p.label}`)
     const tx = await program.methods
       .createProduct(
-        new BN(p.id),
         p.delayMin,
-        new BN(p.coverage6dp),
+        new BN(p.minCoverage6dp),
+        new BN(p.maxCoverage6dp),
+        new BN(p.coverageStep6dp),
         p.premiumBps,
         p.claimHours,
       )
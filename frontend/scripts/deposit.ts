@@ -2,7 +2,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { PublicKey, Connection, clusterApiUrl, Transaction, TransactionInstruction, SystemProgram } from '@solana/web3.js';
 import BN from 'bn.js';
-import { getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
 import { createRequire } from 'module';
 const require = createRequire(import.meta.url);
 const idlJson = require('../src/idl/zyura.json');
@@ -31,27 +31,29 @@ async function main() {
   const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID);
   const [liquidityProviderPda] = PublicKey.findProgramAddressSync([Buffer.from('liquidity_provider'), user.publicKey.toBuffer()], PROGRAM_ID);
 
-  // Accounts
-  const userUsdcAta = getAssociatedTokenAddressSync(USDC_MINT, user.publicKey);
-  const riskPoolVault = getAssociatedTokenAddressSync(USDC_MINT, user.publicKey /* admin stored in config, but in this program risk_pool_vault is admin USDC ATA. We'll derive from config below. */);
-
-  // Fetch config to read admin (vault authority)
+  // Accounts: the vault and LP mint are program PDAs recorded on the config
   const cfgInfo = await connection.getAccountInfo(configPda);
   if (!cfgInfo) throw new Error('Config not initialized');
   const decodedConfig: any = coder.accounts.decode('Config', cfgInfo.data);
-  const adminPubkey = new PublicKey(decodedConfig.admin);
-  const riskPoolVaultAta = getAssociatedTokenAddressSync(USDC_MINT, adminPubkey);
+  const riskPoolVault = new PublicKey(decodedConfig.risk_pool_vault);
+  const lpMint = new PublicKey(decodedConfig.lp_mint);
+  const userUsdcAta = getAssociatedTokenAddressSync(USDC_MINT, user.publicKey);
+  // LP shares land in the depositor's associated token account, created by the program if missing
+  const userLpAta = getAssociatedTokenAddressSync(lpMint, user.publicKey);
 
   // Build ix: deposit_liquidity(amount)
-  const data = coder.instruction.encode('deposit_liquidity', { amount: AMOUNT_6DP } as any);
+  const data = coder.instruction.encode('deposit_liquidity', { amount: AMOUNT_6DP });
 
   const keys = [
     { pubkey: configPda, isWritable: true, isSigner: false },
     { pubkey: liquidityProviderPda, isWritable: true, isSigner: false },
-    { pubkey: riskPoolVaultAta, isWritable: true, isSigner: false },
+    { pubkey: riskPoolVault, isWritable: true, isSigner: false },
+    { pubkey: lpMint, isWritable: true, isSigner: false },
     { pubkey: userUsdcAta, isWritable: true, isSigner: false },
+    { pubkey: userLpAta, isWritable: true, isSigner: false },
     { pubkey: user.publicKey, isWritable: true, isSigner: true },
-    { pubkey: new PublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA'), isWritable: false, isSigner: false },
+    { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+    { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
     { pubkey: SystemProgram.programId, isWritable: false, isSigner: false },
   ];
 
@@ -1,4 +1,5 @@
 import { Connection, Keypair, PublicKey, SystemProgram, Transaction, TransactionInstruction, clusterApiUrl } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token'
 import * as anchor from '@coral-xyz/anchor'
 import * as fs from 'fs'
 import * as path from 'path'
@@ -8,6 +9,7 @@ const PROGRAM_ID = new PublicKey('DWErB1gSbiBBeEaXzy3KEsCbMZCD6sXmrVT9WF9mZgxX')
 const USDC_MINT = new PublicKey(process.env.USDC_MINT || '4sCh4YUdsFuUFTaMyAx3SVnHvHkY9XNq1LX4L6nnWUtv')
 const SWITCHBOARD_PROGRAM_ID = new PublicKey(process.env.SWITCHBOARD_PID || 'SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f')
 const RPC_URL = process.env.NEXT_PUBLIC_RPC_URL || clusterApiUrl('devnet')
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
 const ADMIN_KEYPAIR_PATH = process.env.ADMIN_KEYPAIR_PATH || path.join(process.env.HOME || process.env.USERPROFILE || '', '.config/solana/phantom-devnet.json')
 
 function loadKeypair(fp: string): Keypair {
@@ -26,6 +28,9 @@ async function main() {
 
   // PDAs
   const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID)
+  const [riskPoolVault] = PublicKey.findProgramAddressSync([Buffer.from('risk_pool_vault')], PROGRAM_ID)
+  const [lpMint] = PublicKey.findProgramAddressSync([Buffer.from('lp_mint')], PROGRAM_ID)
+  const [programData] = PublicKey.findProgramAddressSync([PROGRAM_ID.toBuffer()], BPF_LOADER_UPGRADEABLE_PROGRAM_ID)
 
   console.log('Admin:', admin.publicKey.toString())
   console.log('Config PDA:', configPda.toString())
@@ -33,13 +38,21 @@ async function main() {
   // Encode instruction data
   const data = coder.instruction.encode('initialize', {
     admin: admin.publicKey,
-    usdcMint: USDC_MINT,
-    switchboardProgram: SWITCHBOARD_PROGRAM_ID,
-  } as any)
+    usdc_mint: USDC_MINT,
+    switchboard_program: SWITCHBOARD_PROGRAM_ID,
+  })
 
+  // The admin keypair must also be the program's upgrade authority (the deploy wallet)
   const keys = [
     { pubkey: configPda, isSigner: false, isWritable: true },
+    { pubkey: USDC_MINT, isSigner: false, isWritable: false },
+    { pubkey: riskPoolVault, isSigner: false, isWritable: true },
+    { pubkey: lpMint, isSigner: false, isWritable: true },
     { pubkey: admin.publicKey, isSigner: true, isWritable: true },
+    { pubkey: admin.publicKey, isSigner: true, isWritable: false },
+    { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
+    { pubkey: programData, isSigner: false, isWritable: false },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
   ]
 
@@ -4,6 +4,7 @@ import { getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, creat
 import bs58 from 'bs58'
 import BN from 'bn.js'
 import { createRequire } from 'module'
+import { flightPda, flightExposurePda, maxPremiumWithSlippage, nextPolicyId, policyPda, policyholderPda, purchasedPolicyId, quotePremium } from '../src/lib/zyura'
 const require = createRequire(import.meta.url)
 const idlJson = require('../src/idl/zyura.json')
 const fs = require('fs')
@@ -29,9 +30,10 @@ const GITHUB_PATH = process.env.GITHUB_PATH || 'metadata' // Directory path in r
 
 // Inputs
 const PRODUCT_ID = new BN(Number(process.env.PRODUCT_ID ?? 1))
-const POLICY_ID = new BN(Number(process.env.POLICY_ID ?? 1))
 const FLIGHT_NUMBER = process.env.FLIGHT_NUMBER ?? 'AA123'
+// Must match the schedule the settler registered for the flight
 const DEPARTURE_TIME = new BN(Number(process.env.DEPARTURE_UNIX ?? Math.floor(Date.now() / 1000) + 3600))
+// Optional cap on the premium; defaults to the on-chain quote plus 1% slippage
 const PREMIUM_6DP_ENV = process.env.PREMIUM_6DP ? new BN(Number(process.env.PREMIUM_6DP)) : undefined
 const PNR = process.env.PNR ?? ''
 
@@ -219,20 +221,30 @@ async function main() {
   try {
     const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], PROGRAM_ID)
   const [productPda] = PublicKey.findProgramAddressSync([Buffer.from('product'), PRODUCT_ID.toArrayLike(Buffer, 'le', 8)], PROGRAM_ID)
-  const [policyPda] = PublicKey.findProgramAddressSync([Buffer.from('policy'), POLICY_ID.toArrayLike(Buffer, 'le', 8)], PROGRAM_ID)
+  const flight = flightPda(PROGRAM_ID, FLIGHT_NUMBER, Number(DEPARTURE_TIME.toString()))
+  const flightExposure = flightExposurePda(PROGRAM_ID, flight, consumer.publicKey)
+  const policyholder = policyholderPda(PROGRAM_ID, consumer.publicKey)
+  // The program assigns the id from the consumer's Policyholder counter
+  const POLICY_ID = await nextPolicyId(connection, coder, PROGRAM_ID, consumer.publicKey)
+  const policy = policyPda(PROGRAM_ID, consumer.publicKey, POLICY_ID)
   const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s')
 
   // Ensure Consumer USDC ATA exists
   const userAta = await getOrCreateAssociatedTokenAccount(connection, consumer, USDC_MINT, consumer.publicKey)
   const userUsdcAccount = userAta.address
 
-  // Use Phantom/admin USDC ATA as the protocol risk pool vault
   const configAccountInfo = await connection.getAccountInfo(configPda)
   if (!configAccountInfo) throw new Error('Config account not found. Run admin initialize first.')
   const decodedConfig: any = coder.accounts.decode('Config', configAccountInfo.data)
-  const adminPubkey = new PublicKey(decodedConfig.admin)
-  const adminAta = await getOrCreateAssociatedTokenAccount(connection, consumer, USDC_MINT, adminPubkey)
-  const riskPoolVault = adminAta.address
+  const riskPoolVault = new PublicKey(decodedConfig.risk_pool_vault)
+
+  // Flights are registered on-chain by the settler; only registered schedules can be insured
+  const flightInfo = await connection.getAccountInfo(flight)
+  if (!flightInfo) throw new Error(`Flight ${FLIGHT_NUMBER} is not registered for that departure date`)
+  const decFlight: any = coder.accounts.decode('Flight', flightInfo.data)
+  if (decFlight.scheduled_departure.toString() !== DEPARTURE_TIME.toString()) {
+    throw new Error(`Flight ${FLIGHT_NUMBER} is scheduled to depart at ${decFlight.scheduled_departure.toString()}, not ${DEPARTURE_TIME.toString()}`)
+  }
 
   // NFT mint and ATA
   const policyNftMint = Keypair.generate()
@@ -252,14 +264,19 @@ async function main() {
   let svg = fs.readFileSync(svgPath, 'utf8')
   const departureIso = new Date(Number(DEPARTURE_TIME.toString()) * 1000).toISOString()
   
-  // Compute required premium from product if not provided
+  // Quote the premium for the product's full coverage unless a cap is provided
   const productInfo = await connection.getAccountInfo(productPda)
   if (!productInfo) throw new Error('Product not found')
   const decProduct: any = coder.accounts.decode('Product', productInfo.data)
-  const coverage6 = new BN((decProduct.coverage_amount as any).toString())
-  const rateBps = Number((decProduct.premium_rate_bps as any).toString())
-  const REQUIRED_PREMIUM = coverage6.mul(new BN(rateBps)).div(new BN(10_000))
-  const PREMIUM_6DP = PREMIUM_6DP_ENV ?? REQUIRED_PREMIUM
+  const coverage6 = new BN((decProduct.max_coverage as any).toString())
+  const PREMIUM_6DP = PREMIUM_6DP_ENV ?? await quotePremium(
+    connection,
+    coder,
+    PROGRAM_ID,
+    { config: configPda, product: productPda, riskPoolVault },
+    coverage6,
+    consumer.publicKey,
+  )
 
   // Convert 6dp values to USD format (divide by 1,000,000)
   const premiumUsd = (Number(PREMIUM_6DP.toString()) / 1_000_000).toLocaleString('en-US', {
@@ -318,17 +335,20 @@ async function main() {
 
   // Build instruction via coder
   const data = coder.instruction.encode('purchase_policy', {
-    policy_id: POLICY_ID,
     flight_number: FLIGHT_NUMBER,
     departure_time: DEPARTURE_TIME,
-    premium_amount: PREMIUM_6DP,
+    coverage_amount: coverage6,
+    max_premium: PREMIUM_6DP_ENV ?? maxPremiumWithSlippage(PREMIUM_6DP),
     create_metadata: true,
     metadata_uri: metadataUri,
   } as any)
   const keys = [
     { pubkey: configPda, isWritable: true, isSigner: false },
     { pubkey: productPda, isWritable: true, isSigner: false },
-    { pubkey: policyPda, isWritable: true, isSigner: false },
+    { pubkey: policyholder, isWritable: true, isSigner: false },
+    { pubkey: policy, isWritable: true, isSigner: false },
+    { pubkey: flight, isWritable: true, isSigner: false },
+    { pubkey: flightExposure, isWritable: true, isSigner: false },
     { pubkey: riskPoolVault, isWritable: true, isSigner: false },
     { pubkey: userUsdcAccount, isWritable: true, isSigner: false },
     { pubkey: consumer.publicKey, isWritable: true, isSigner: true },
@@ -354,7 +374,8 @@ async function main() {
   tx.recentBlockhash = blockhash
   tx.partialSign(policyNftMint)
   const sig = await provider.sendAndConfirm(tx, [policyNftMint])
-  console.log('Purchased policy:', sig)
+  const purchasedId = (await purchasedPolicyId(connection, PROGRAM_ID, sig)) ?? POLICY_ID
+  console.log(`Purchased policy ${purchasedId.toString()}:`, sig)
 
   // Update flight repository with policy information
   try {
@@ -364,7 +385,7 @@ async function main() {
       flight_number: FLIGHT_NUMBER,
       date: new Date(Number(DEPARTURE_TIME.toString()) * 1000).toISOString().split('T')[0], // YYYY-MM-DD
       departure_unix: Number(DEPARTURE_TIME.toString()), // Scheduled departure time
-      policyId: Number(POLICY_ID.toString()),
+      policyId: Number(purchasedId.toString()),
       pnr: PNR || undefined,
       passenger: PNR ? { name: "Script User", email: "script@example.com" } : undefined, // Placeholder passenger data
       wallet: consumer.publicKey.toString(),
@@ -27,7 +27,15 @@ const PHANTOM_SECRET_KEY = process.env.PHANTOM_SECRET_KEY // base58-encoded 64-b
 // Product params (6dp) — update existing product with these values (can be overridden via env)
 const PRODUCT_ID = new BN(process.env.PRODUCT_ID ? Number(process.env.PRODUCT_ID) : 2)
 const DELAY_MIN = process.env.DELAY_MIN ? Number(process.env.DELAY_MIN) : 60
-const COVERAGE_6DP = new BN(process.env.COVERAGE_6DP ? Number(process.env.COVERAGE_6DP) : 100_000_000) // 100.000000
+// Coverage range (6dp): set MAX_COVERAGE_6DP to queue set_coverage_range instead of update_product,
+// since a product holds one pending change at a time. MIN defaults to MAX and STEP to MAX - MIN (or MAX)
+const MAX_COVERAGE_6DP = process.env.MAX_COVERAGE_6DP ? new BN(Number(process.env.MAX_COVERAGE_6DP)) : undefined
+const MIN_COVERAGE_6DP = process.env.MIN_COVERAGE_6DP ? new BN(Number(process.env.MIN_COVERAGE_6DP)) : MAX_COVERAGE_6DP
+const COVERAGE_STEP_6DP = process.env.COVERAGE_STEP_6DP
+  ? new BN(Number(process.env.COVERAGE_STEP_6DP))
+  : MAX_COVERAGE_6DP && MIN_COVERAGE_6DP && MAX_COVERAGE_6DP.gt(MIN_COVERAGE_6DP)
+    ? MAX_COVERAGE_6DP.sub(MIN_COVERAGE_6DP)
+    : MAX_COVERAGE_6DP
 // Set premium via percentage: e.g., PREMIUM_PERCENT=10 -> 10% (=1000 bps)
 const PREMIUM_PERCENT = process.env.PREMIUM_PERCENT ? Number(process.env.PREMIUM_PERCENT) : 10
 const PREMIUM_BPS = Math.round((isFinite(PREMIUM_PERCENT) ? PREMIUM_PERCENT : 10) * 100)
@@ -65,20 +73,32 @@ async function main() {
     process.exit(1)
   }
 
-  const data = coder.instruction.encode('update_product', {
-    id: PRODUCT_ID, // u64
-    delay_threshold_minutes: DELAY_MIN, // u32
-    coverage_amount: COVERAGE_6DP, // u64
-    premium_rate_bps: PREMIUM_BPS, // u16
-    claim_window_hours: CLAIM_HOURS, // u32
-  } as any)
-
-  const keys = [
-    { pubkey: configPda, isWritable: true, isSigner: false },
-    { pubkey: productPda, isWritable: true, isSigner: false },
-    { pubkey: admin.publicKey, isWritable: true, isSigner: true },
-    { pubkey: SystemProgram.programId, isWritable: false, isSigner: false },
-  ]
+  // Both instructions queue a timelocked change; apply it after the eta with execute_product_change
+  const data = MAX_COVERAGE_6DP
+    ? coder.instruction.encode('set_coverage_range', {
+        min_coverage: MIN_COVERAGE_6DP, // u64
+        max_coverage: MAX_COVERAGE_6DP, // u64
+        coverage_step: COVERAGE_STEP_6DP, // u64
+      })
+    : coder.instruction.encode('update_product', {
+        id: PRODUCT_ID, // u64
+        delay_threshold_minutes: DELAY_MIN, // u32
+        premium_rate_bps: PREMIUM_BPS, // u16
+        claim_window_hours: CLAIM_HOURS, // u32
+      })
+
+  const keys = MAX_COVERAGE_6DP
+    ? [
+        { pubkey: configPda, isWritable: false, isSigner: false },
+        { pubkey: productPda, isWritable: true, isSigner: false },
+        { pubkey: admin.publicKey, isWritable: false, isSigner: true },
+      ]
+    : [
+        { pubkey: configPda, isWritable: true, isSigner: false },
+        { pubkey: productPda, isWritable: true, isSigner: false },
+        { pubkey: admin.publicKey, isWritable: true, isSigner: true },
+        { pubkey: SystemProgram.programId, isWritable: false, isSigner: false },
+      ]
 
   const ix = new TransactionInstruction({ programId: PROGRAM_ID, keys, data })
   const { blockhash } = await connection.getLatestBlockhash()
@@ -86,7 +106,7 @@ async function main() {
   tx.feePayer = admin.publicKey
   tx.recentBlockhash = blockhash
   const sig = await provider.sendAndConfirm(tx, [])
-  console.log('Updated product:', sig)
+  console.log(MAX_COVERAGE_6DP ? 'Queued coverage range change:' : 'Queued product update:', sig)
 }
 
 main().catch((e) => {
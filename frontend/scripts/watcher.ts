@@ -2,14 +2,14 @@
   Policy watcher (no on-chain changes):
   - Scans all Active policies
   - For each, fetches flight delay from flight metadata repo (or API/mock)
-  - If delay >= product threshold, calls process_payout (admin signs)
+  - If the delay reaches the policy's first payout band (or its threshold), calls
+    process_payout with a Delayed outcome (settler signs)
 
   Required:
-  - ADMIN_KEYPAIR: Path to admin wallet keypair file
+  - ADMIN_KEYPAIR: Path to a keypair holding the Settler role (or the admin)
     Default: $HOME/.config/solana/phantom-devnet.json
 
   Optional:
-  - RISK_POOL_VAULT: USDC token account (auto-derived from admin if not provided)
   - SOLANA_RPC: RPC URL (default: https://api.devnet.solana.com)
   - USE_FLIGHT_METADATA: Set to "true" to use GitHub flight metadata repo
   - GITHUB_FLIGHT_REPO: Flight metadata repo (default: alienx5499/zyura-flight-metadata)
@@ -41,21 +41,15 @@ type PolicyAccount = {
   policyholder: PublicKey
   productId: anchor.BN
   flightNumber: string
+  flight: PublicKey
   departureTime: anchor.BN
   premiumPaid: anchor.BN
   coverageAmount: anchor.BN
+  // Smallest delay that pays under the policy's snapshotted terms
+  minPayableDelayMinutes: number
   status: any
 }
 
-type ProductAccount = {
-  id: anchor.BN
-  delayThresholdMinutes: number
-  coverageAmount: anchor.BN
-  premiumRateBps: number
-  claimWindowHours: number
-  active: boolean
-}
-
 async function loadKeypair(fp: string): Promise<Keypair> {
   const fs = await import('fs')
   const raw = JSON.parse(fs.readFileSync(fp, 'utf8'))
@@ -296,13 +290,10 @@ async function main() {
     usdcMint = new PublicKey('4sCh4YUdsFuUFTaMyAx3SVnHvHkY9XNq1LX4L6nnWUtv')
   }
 
-  // Auto-derive risk pool vault from admin's USDC ATA (or use provided)
-  const riskPoolVaultStr = process.env.RISK_POOL_VAULT
-  const riskPoolVault = riskPoolVaultStr 
-    ? new PublicKey(riskPoolVaultStr)
-    : getAssociatedTokenAddressSync(usdcMint, admin.publicKey)
+  // Program-owned vault PDA recorded on the config
+  const riskPoolVault = new PublicKey(configAcc.risk_pool_vault)
   
-  console.log(`Admin: ${admin.publicKey.toBase58()}`)
+  console.log(`Settler: ${admin.publicKey.toBase58()}`)
   console.log(`Risk Pool Vault: ${riskPoolVault.toBase58()}`)
 
   // Fetch all policies using getProgramAccounts
@@ -356,6 +347,12 @@ async function main() {
         return new anchor.BN(val.toString())
       }
         
+        // With delay bands the first band is the smallest payable delay, otherwise the single threshold
+        const delayBands: any[] = decoded.delay_bands || []
+        const minPayableDelayMinutes = delayBands.length > 0
+          ? Number(delayBands[0].min_delay_minutes)
+          : Number(decoded.delay_threshold_minutes || 0)
+        
         activePolicies.push({
           publicKey: acc.pubkey,
           account: {
@@ -363,9 +360,11 @@ async function main() {
             policyholder,
             productId: toBN(decoded.product_id),
             flightNumber: decoded.flight_number || '',
+            flight: new PublicKey(decoded.flight),
             departureTime: toBN(decoded.departure_time),
             premiumPaid: toBN(decoded.premium_paid),
             coverageAmount: toBN(decoded.coverage_amount),
+            minPayableDelayMinutes,
             status: decoded.status
           }
         })
@@ -384,49 +383,15 @@ async function main() {
 
   console.log(`Scanning ${activePolicies.length} active policies...`)
 
-  // Helper to convert values to BN (BorshCoder may return hex strings or BN objects)
-  const toBN = (val: any): anchor.BN => {
-    if (!val) return new anchor.BN(0)
-    if (val instanceof anchor.BN) return val
-    if (typeof val === 'string') {
-      if (val.startsWith('0x')) return new anchor.BN(val.slice(2), 'hex')
-      try {
-        return new anchor.BN(val, 'hex')
-      } catch {
-        return new anchor.BN(val, 10)
-      }
-    }
-    return new anchor.BN(val.toString())
-  }
-
   for (const p of activePolicies) {
     try {
       const policy = p.account
 
-      // Fetch product for threshold
+      // Payout terms are snapshotted on the policy; the product account is only passed for its liability totals
       const [productPda] = PublicKey.findProgramAddressSync(
         [Buffer.from('product'), policy.productId.toArrayLike(Buffer, 'le', 8)],
         PROGRAM_ID,
       )
-      const productInfo = await connection.getAccountInfo(productPda)
-      if (!productInfo) {
-        console.log(`Policy ${policy.id.toString()}: Product ${policy.productId.toString()} not found`)
-        continue
-      }
-      const productDecoded: any = coder.accounts.decode('Product', productInfo.data)
-      // Product fields are also snake_case
-      const product = {
-        id: toBN(productDecoded.id),
-        delayThresholdMinutes: productDecoded.delay_threshold_minutes || 0,
-        coverageAmount: toBN(productDecoded.coverage_amount),
-        premiumRateBps: productDecoded.premium_rate_bps || 0,
-        claimWindowHours: productDecoded.claim_window_hours || 0,
-        active: productDecoded.active || false
-      }
-      if (!product.active) {
-        console.log(`Policy ${policy.id.toString()}: Product ${policy.productId.toString()} is inactive`)
-        continue
-      }
 
       // Derive policyholder USDC ATA
       const policyholderUsdcAta = getAssociatedTokenAddressSync(usdcMint, policy.policyholder)
@@ -438,20 +403,21 @@ async function main() {
         : undefined
 
       const delay = await fetchDelayMinutes(policy.flightNumber, dateStr)
-      console.log(`Policy ${policy.id.toString()} flight ${policy.flightNumber} delay=${delay} min (threshold=${product.delayThresholdMinutes})`)
+      console.log(`Policy ${policy.id.toString()} flight ${policy.flightNumber} delay=${delay} min (pays from ${policy.minPayableDelayMinutes})`)
 
-    if (delay >= product.delayThresholdMinutes) {
+    if (delay > 0 && delay >= policy.minPayableDelayMinutes) {
       try {
         // Build transaction using coder
         const data = coder.instruction.encode('process_payout', {
           policy_id: policy.id,
-          delay_minutes: delay
-        } as any)
+          outcome: { Delayed: { minutes: delay } }
+        })
 
         const keys = [
           { pubkey: configPda, isWritable: true, isSigner: false },
           { pubkey: productPda, isWritable: true, isSigner: false },
           { pubkey: p.publicKey, isWritable: true, isSigner: false },
+          { pubkey: policy.flight, isWritable: true, isSigner: false },
           { pubkey: riskPoolVault, isWritable: true, isSigner: false },
           { pubkey: policyholderUsdcAta, isWritable: true, isSigner: false },
           { pubkey: admin.publicKey, isWritable: true, isSigner: true },
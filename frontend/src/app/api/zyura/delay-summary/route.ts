@@ -120,7 +120,7 @@ function parseFlights(qs: URLSearchParams): FlightQuery[] {
   return unique;
 }
 
-async function fetchPoliciesFromChain(policyIds: string[]): Promise<FlightQuery[]> {
+async function fetchPoliciesFromChain(wallet: PublicKey, policyIds: string[]): Promise<FlightQuery[]> {
   const program = await getProgram();
   const flights: FlightQuery[] = [];
 
@@ -129,12 +129,12 @@ async function fetchPoliciesFromChain(policyIds: string[]): Promise<FlightQuery[
       const policyIdNum = Number(policyIdStr);
       if (!isFinite(policyIdNum)) continue;
       
-      // Derive policy PDA using little-endian u64 bytes (same as contract)
+      // Policy ids are numbered per wallet, so the PDA is ["policy", wallet, id as little-endian u64]
       const policyIdBytes = Buffer.allocUnsafe(8);
       policyIdBytes.writeBigUInt64LE(BigInt(policyIdNum), 0);
       
       const [policyPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("policy"), policyIdBytes],
+        [Buffer.from("policy"), wallet.toBuffer(), policyIdBytes],
         PROGRAM_ID
       );
 
@@ -164,8 +164,17 @@ export async function GET(req: NextRequest) {
   let flights: FlightQuery[] = [];
 
   if (policyIds.length > 0) {
-    // Fetch policies from on-chain
-    flights = await fetchPoliciesFromChain(policyIds);
+    // Fetch policies from on-chain; ids only identify a policy together with its holder's wallet
+    let wallet: PublicKey;
+    try {
+      wallet = new PublicKey(searchParams.get("wallet") || "");
+    } catch {
+      return NextResponse.json(
+        { error: "policy= lookups need the policyholder's wallet=<pubkey>" },
+        { status: 400 }
+      );
+    }
+    flights = await fetchPoliciesFromChain(wallet, policyIds);
   } else {
     // Fallback to manual flight params
     flights = parseFlights(searchParams);
@@ -174,7 +183,7 @@ export async function GET(req: NextRequest) {
   if (flights.length === 0) {
     return NextResponse.json(
       {
-        error: "No flights provided. Use policy=123&policy=456 (fetches from on-chain) or flight=AA123&flight=BB456&date=YYYY-MM-DD (manual)",
+        error: "No flights provided. Use wallet=<pubkey>&policy=0&policy=1 (fetches from on-chain) or flight=AA123&flight=BB456&date=YYYY-MM-DD (manual)",
       },
       { status: 400 }
     );
@@ -179,12 +179,16 @@ export async function POST(req: NextRequest) {
     let coverageAmount: number | undefined;
     let premiumPaid: number | undefined;
 
-    if (body.policyId) {
+    // Policy ids are numbered per wallet from 0, so the PDA needs both and id 0 is valid
+    if (body.policyId !== undefined && body.wallet) {
       try {
         const program = await getProgram();
         const idBytes = Buffer.allocUnsafe(8);
         idBytes.writeBigUInt64LE(BigInt(body.policyId), 0);
-        const [policyPda] = PublicKey.findProgramAddressSync([Buffer.from("policy"), idBytes], PROGRAM_ID);
+        const [policyPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("policy"), new PublicKey(body.wallet).toBuffer(), idBytes],
+          PROGRAM_ID
+        );
         const policy: any = await program.account.policy.fetch(policyPda);
         policyholder = new PublicKey(policy.policyholder).toBase58();
         coverageAmount = Number(policy.coverageAmount?.toString() || "0");
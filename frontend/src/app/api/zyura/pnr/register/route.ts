@@ -38,20 +38,23 @@ const FLIGHT_REPO = process.env.GITHUB_FLIGHT_REPO || "alienx5499/zyura-flight-m
 export async function POST(req: NextRequest) {
   try {
     const body = (await req.json()) as RegisterBody;
-    if (!body?.pnr || !body?.policyId) return NextResponse.json({ error: "pnr and policyId are required" }, { status: 400 });
+    if (!body?.pnr || body?.policyId === undefined) return NextResponse.json({ error: "pnr and policyId are required" }, { status: 400 });
     if (body.pnr.length !== 6) return NextResponse.json({ error: "PNR must be exactly 6 characters" }, { status: 400 });
 
     // Populate from-chain if missing
     let { flightNumber, departureUnix, policyholder, date } = body;
-    if (!flightNumber || !departureUnix || !policyholder || !date) {
+    // Policy ids are numbered per wallet, so the policy can only be looked up with its policyholder
+    if ((!flightNumber || !departureUnix || !date) && policyholder) {
       const program = await getProgram();
       const idBytes = Buffer.allocUnsafe(8);
       idBytes.writeBigUInt64LE(BigInt(body.policyId), 0);
-      const [policyPda] = PublicKey.findProgramAddressSync([Buffer.from("policy"), idBytes], PROGRAM_ID);
+      const [policyPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("policy"), new PublicKey(policyholder).toBuffer(), idBytes],
+        PROGRAM_ID
+      );
       const policy: any = await program.account.policy.fetch(policyPda);
       flightNumber = flightNumber || policy.flightNumber || "";
       departureUnix = departureUnix || Number(policy.departureTime?.toString?.() || "0");
-      policyholder = policyholder || new PublicKey(policy.policyholder).toBase58();
       if (!date && departureUnix > 0) {
         date = new Date(departureUnix * 1000).toISOString().slice(0, 10);
       }
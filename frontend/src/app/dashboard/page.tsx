@@ -12,6 +12,16 @@ import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from "@solana/spl-tok
 import BN from "bn.js";
 import bs58 from "bs58";
 import idlJson from "@/idl/zyura.json";
+import {
+  flightPda,
+  flightExposurePda,
+  maxPremiumWithSlippage,
+  nextPolicyId,
+  policyPda,
+  policyholderPda,
+  purchasedPolicyId,
+  quotePremium,
+} from "@/lib/zyura";
 import { motion, AnimatePresence } from "framer-motion";
 
 // Import new components
@@ -379,21 +389,55 @@ export default function DashboardPage() {
       const departureDateTime = new Date(`${departureDate}T${departureTime}:00Z`);
       const departureUnix = Math.floor(departureDateTime.getTime() / 1000);
       const departureTimeBn = new BN(departureUnix);
-      const policyId = Math.floor(Date.now() / 1000) % 1000000;
 
-      const productInfoAcc = await connection.getAccountInfo(
-        PublicKey.findProgramAddressSync(
-          [Buffer.from("product"), new BN(parseInt(productId, 10)).toArrayLike(Buffer, "le", 8)],
-          PROGRAM_ID
-        )[0]
+      // Derive PDAs
+      const [configPda] = PublicKey.findProgramAddressSync([Buffer.from("config")], PROGRAM_ID);
+      const [productPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), new BN(parseInt(productId, 10)).toArrayLike(Buffer, "le", 8)],
+        PROGRAM_ID
       );
+      const flightAccount = flightPda(PROGRAM_ID, flightNumber, departureUnix);
+      const flightExposureAccount = flightExposurePda(PROGRAM_ID, flightAccount, publicKey);
+      const policyholderAccount = policyholderPda(PROGRAM_ID, publicKey);
+
+      // Flight schedules are registered on-chain by the protocol's settler, not by buyers
+      const flightInfoAcc = await connection.getAccountInfo(flightAccount);
+      if (!flightInfoAcc) {
+        throw new Error("This flight is not open for cover yet. Check the flight number and departure date.");
+      }
+      const decodedFlight: any = coder.accounts.decode("Flight", flightInfoAcc.data);
+      const scheduledDeparture = Number(decodedFlight.scheduled_departure.toString());
+      if (scheduledDeparture !== departureUnix) {
+        throw new Error(`This flight is scheduled to depart at ${new Date(scheduledDeparture * 1000).toISOString()}`);
+      }
+
+      const configAccountInfo = await connection.getAccountInfo(configPda);
+      if (!configAccountInfo) {
+        throw new Error("Protocol not initialized. Please contact support.");
+      }
+      const decodedConfig: any = coder.accounts.decode("Config", configAccountInfo.data);
+      const riskPoolVault = new PublicKey(decodedConfig.risk_pool_vault);
+
+      const productInfoAcc = await connection.getAccountInfo(productPda);
       if (!productInfoAcc) {
         throw new Error("Selected product not found");
       }
       const decodedProduct: any = coder.accounts.decode("Product", productInfoAcc.data);
-      const coverageAmount6dp = new BN((decodedProduct.coverage_amount as any).toString());
-      const premiumRateBps: number = Number((decodedProduct.premium_rate_bps as any).toString());
-      const premiumAmount = coverageAmount6dp.mul(new BN(premiumRateBps)).div(new BN(10_000));
+      const coverageAmount6dp = new BN((decodedProduct.max_coverage as any).toString());
+      // Quoted on-chain (the rate rises with pool utilization); the purchase caps the premium at the quote plus slippage
+      const premiumAmount = await quotePremium(
+        connection,
+        coder,
+        PROGRAM_ID,
+        { config: configPda, product: productPda, riskPoolVault },
+        coverageAmount6dp,
+        publicKey
+      );
+
+      // The program numbers each wallet's policies from its Policyholder counter
+      const nextId = await nextPolicyId(connection, coder, PROGRAM_ID, publicKey);
+      const policyAccount = policyPda(PROGRAM_ID, publicKey, nextId);
+      const policyId = nextId.toNumber();
 
       toast.info("Preparing metadata and assets...");
 
@@ -482,49 +526,7 @@ export default function DashboardPage() {
 
       toast.info("Building transaction...");
 
-      // Derive PDAs
-      const [configPda] = PublicKey.findProgramAddressSync([Buffer.from("config")], PROGRAM_ID);
-      const [productPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("product"), new BN(parseInt(productId, 10)).toArrayLike(Buffer, "le", 8)],
-        PROGRAM_ID
-      );
-      const [policyPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("policy"), new BN(policyId).toArrayLike(Buffer, "le", 8)],
-        PROGRAM_ID
-      );
-
-      // Get config account
-      const configAccountInfo = await connection.getAccountInfo(configPda);
-      if (!configAccountInfo) {
-        throw new Error("Protocol not initialized. Please contact support.");
-      }
-
-      let decodedConfig: any;
-      try {
-        decodedConfig = coder.accounts.decode("Config", configAccountInfo.data);
-      } catch (err: any) {
-        if (err.message?.includes("discriminator") || err.message?.includes("Invalid account discriminator")) {
-          const dataWithoutDiscriminator = configAccountInfo.data.slice(8);
-          const adminBytes = dataWithoutDiscriminator.slice(0, 32);
-          const usdcMintBytes = dataWithoutDiscriminator.slice(32, 64);
-          const switchboardProgramBytes = dataWithoutDiscriminator.slice(64, 96);
-          const paused = dataWithoutDiscriminator[96] === 1;
-          const bump = dataWithoutDiscriminator[97];
-          decodedConfig = {
-            admin: new PublicKey(adminBytes).toString(),
-            usdc_mint: new PublicKey(usdcMintBytes).toString(),
-            switchboard_program: new PublicKey(switchboardProgramBytes).toString(),
-            paused: paused,
-            bump: bump,
-          };
-        } else {
-          throw err;
-        }
-      }
-
-      const adminPubkey = new PublicKey(decodedConfig.admin);
       const userUsdcAccount = getAssociatedTokenAddressSync(USDC_MINT, publicKey);
-      const riskPoolVault = getAssociatedTokenAddressSync(USDC_MINT, adminPubkey);
       const policyNftMint = Keypair.generate();
       const userPolicyNftAta = getAssociatedTokenAddressSync(policyNftMint.publicKey, publicKey);
 
@@ -553,10 +555,10 @@ export default function DashboardPage() {
 
       // Build instruction
       const data = coder.instruction.encode("purchase_policy", {
-        policy_id: new BN(policyId),
         flight_number: flightNumber,
         departure_time: departureTimeBn,
-        premium_amount: premiumAmount,
+        coverage_amount: coverageAmount6dp,
+        max_premium: maxPremiumWithSlippage(premiumAmount),
         create_metadata: true,
         metadata_uri: metadataUri,
       } as any);
@@ -564,7 +566,10 @@ export default function DashboardPage() {
       const keys = [
         { pubkey: configPda, isWritable: true, isSigner: false },
         { pubkey: productPda, isWritable: true, isSigner: false },
-        { pubkey: policyPda, isWritable: true, isSigner: false },
+        { pubkey: policyholderAccount, isWritable: true, isSigner: false },
+        { pubkey: policyAccount, isWritable: true, isSigner: false },
+        { pubkey: flightAccount, isWritable: true, isSigner: false },
+        { pubkey: flightExposureAccount, isWritable: true, isSigner: false },
         { pubkey: riskPoolVault, isWritable: true, isSigner: false },
         { pubkey: userUsdcAccount, isWritable: true, isSigner: false },
         { pubkey: publicKey, isWritable: true, isSigner: true },
@@ -636,6 +641,7 @@ export default function DashboardPage() {
       }
 
       setLastTxSig(signature);
+      const purchasedId = (await purchasedPolicyId(connection, PROGRAM_ID, signature))?.toNumber() ?? policyId;
 
       toast.success("Insurance purchased successfully!", {
         description: `Transaction: ${signature.slice(0, 8)}...${signature.slice(-8)}`
@@ -652,7 +658,7 @@ export default function DashboardPage() {
             flight_number: flightNumber,
             date: departureDate, // YYYY-MM-DD format
             departure_unix: departureUnix, // Scheduled departure time
-            policyId: policyId,
+            policyId: purchasedId,
             pnr: pnr,
             passenger: fetchedPassenger,
             wallet: publicKey.toString(),
@@ -24,17 +24,23 @@ export function ProductStatsCard({ productInfo }: ProductStatsCardProps) {
 
   const idNum = toNumber(productInfo.product_id ?? productInfo.productId ?? productInfo.id);
   const delayMin = toNumber(productInfo.delay_threshold_minutes ?? productInfo.delayThresholdMinutes);
-  const coverageAmount = toNumber(productInfo.coverage_amount ?? productInfo.coverageAmount);
+  const minCoverage = toNumber(productInfo.min_coverage ?? productInfo.minCoverage);
+  const maxCoverage = toNumber(productInfo.max_coverage ?? productInfo.maxCoverage);
   const premiumBps = toNumber(productInfo.premium_rate_bps ?? productInfo.premiumRateBps);
   const claimHours = toNumber(productInfo.claim_window_hours ?? productInfo.claimWindowHours);
   const isActive = productInfo.active !== undefined ? Boolean(productInfo.active) : undefined;
 
-  const coverageUsd = coverageAmount
-    ? (coverageAmount / 1_000_000).toLocaleString('en-US', {
-        style: 'currency',
-        currency: 'USD',
-        minimumFractionDigits: 2,
-      })
+  const toUsd = (amount6dp: number) =>
+    (amount6dp / 1_000_000).toLocaleString('en-US', {
+      style: 'currency',
+      currency: 'USD',
+      minimumFractionDigits: 2,
+    });
+  // Buyers pick their coverage within the product's range
+  const coverageUsd = minCoverage && maxCoverage
+    ? minCoverage === maxCoverage
+      ? toUsd(maxCoverage)
+      : `${toUsd(minCoverage)} – ${toUsd(maxCoverage)}`
     : '—';
 
   const premiumPct = premiumBps ? `${(premiumBps / 100).toFixed(2)}%` : '—';
@@ -74,7 +80,7 @@ export function ProductStatsCard({ productInfo }: ProductStatsCardProps) {
         </div>
 
             <div className="rounded-lg border border-gray-800 bg-gray-900/30 p-4">
-          <div className="text-xs text-gray-400 mb-1">Coverage Amount</div>
+          <div className="text-xs text-gray-400 mb-1">Coverage Range</div>
           <div className="text-emerald-400 text-lg font-semibold">{coverageUsd}</div>
         </div>
 
@@ -0,0 +1,120 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Connection, PublicKey, TransactionInstruction, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
+import BN from "bn.js";
+
+const FLIGHT_CODE_LEN = 8;
+const SECONDS_PER_DAY = 86_400;
+
+/** Premium increase tolerated between the quote and the purchase landing (1%), as pool utilization can move. */
+export const PREMIUM_SLIPPAGE_BPS = 100;
+
+/**
+ * Mirrors the program's `flight::normalize_flight_number`: "zy 0042" -> "ZY42".
+ * Throws on flight numbers the program rejects.
+ */
+export function normalizeFlightNumber(raw: string): string {
+  const match = raw.replace(/ /g, "").toUpperCase().match(/^([A-Z]{3}|[A-Z0-9]{2})(\d{1,4})([A-Z]?)$/);
+  if (!match || /^\d{2}$/.test(match[1]) || Number(match[2]) === 0) {
+    throw new Error(`Invalid flight number: ${raw}`);
+  }
+  return `${match[1]}${Number(match[2])}${match[3]}`;
+}
+
+/**
+ * Flight schedule registered by the protocol's settler: one account per normalized
+ * flight code and UTC departure date. Policies can only be bought on registered flights.
+ */
+export function flightPda(programId: PublicKey, flightNumber: string, departureUnix: number): PublicKey {
+  const code = Buffer.alloc(FLIGHT_CODE_LEN);
+  code.write(normalizeFlightNumber(flightNumber));
+  const departureDate = new BN(Math.floor(departureUnix / SECONDS_PER_DAY));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("flight"), code, departureDate.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+export function flightExposurePda(programId: PublicKey, flight: PublicKey, owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("flight_exposure"), flight.toBuffer(), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+export function policyholderPda(programId: PublicKey, owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("policyholder"), owner.toBuffer()], programId)[0];
+}
+
+export function policyPda(programId: PublicKey, owner: PublicKey, policyId: BN): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("policy"), owner.toBuffer(), policyId.toArrayLike(Buffer, "le", 8)],
+    programId
+  )[0];
+}
+
+/**
+ * Id the program will assign to `owner`'s next policy: the count on their Policyholder
+ * account, or 0 before their first purchase. A purchase built against a stale count
+ * fails on the policy address rather than creating a policy under another id.
+ */
+export async function nextPolicyId(
+  connection: Connection,
+  coder: anchor.BorshCoder,
+  programId: PublicKey,
+  owner: PublicKey
+): Promise<BN> {
+  const info = await connection.getAccountInfo(policyholderPda(programId, owner));
+  if (!info) return new BN(0);
+  const holder: any = coder.accounts.decode("Policyholder", info.data);
+  return new BN(holder.policy_count.toString());
+}
+
+function decodeU64ReturnData(programId: PublicKey, returnData?: { programId: string; data: [string, string] } | null): BN | null {
+  if (!returnData || returnData.programId !== programId.toBase58()) return null;
+  return new BN(Buffer.from(returnData.data[0], "base64").subarray(0, 8), "le");
+}
+
+/** Policy id returned by a confirmed `purchase_policy` transaction. */
+export async function purchasedPolicyId(connection: Connection, programId: PublicKey, signature: string): Promise<BN | null> {
+  const tx = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  return decodeU64ReturnData(programId, tx?.meta?.returnData as any);
+}
+
+/** Premium the program currently charges for `coverageAmount` of a product, via a simulated `quote_premium`. */
+export async function quotePremium(
+  connection: Connection,
+  coder: anchor.BorshCoder,
+  programId: PublicKey,
+  accounts: { config: PublicKey; product: PublicKey; riskPoolVault: PublicKey },
+  coverageAmount: BN,
+  payer: PublicKey
+): Promise<BN> {
+  const ix = new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: accounts.config, isWritable: false, isSigner: false },
+      { pubkey: accounts.product, isWritable: false, isSigner: false },
+      { pubkey: accounts.riskPoolVault, isWritable: false, isSigner: false },
+    ],
+    data: coder.instruction.encode("quote_premium", { coverage_amount: coverageAmount }),
+  });
+  const { blockhash } = await connection.getLatestBlockhash();
+  const message = new TransactionMessage({ payerKey: payer, recentBlockhash: blockhash, instructions: [ix] }).compileToV0Message();
+  const { value } = await connection.simulateTransaction(new VersionedTransaction(message), { sigVerify: false });
+  if (value.err) {
+    throw new Error(`Premium quote failed: ${JSON.stringify(value.err)}`);
+  }
+  const premium = decodeU64ReturnData(programId, value.returnData as any);
+  if (!premium) {
+    throw new Error("Premium quote returned no data");
+  }
+  return premium;
+}
+
+/** `max_premium` for a purchase quoted at `quoted`, allowing `slippageBps` of increase (rounded up). */
+export function maxPremiumWithSlippage(quoted: BN, slippageBps: number = PREMIUM_SLIPPAGE_BPS): BN {
+  return quoted.muln(10_000 + slippageBps).addn(9_999).divn(10_000);
+}